@@ -6,6 +6,31 @@ extern crate prost_build;
 
 fn main() -> Result<()> {
     println!("cargo:rerun-if-changed=src/license_protocol.proto");
-    Config::new().out_dir("src").compile_protos(&["src/license_protocol.proto"], &["src/"])?;
+    // The committed src/generated/license_protocol.rs is used instead, no protoc required.
+    if cfg!(feature = "pregenerated") && !cfg!(feature = "protojson") {
+        return Ok(());
+    }
+    #[cfg(feature = "vendored-protoc")]
+    if std::env::var_os("PROTOC").is_none()
+        && std::process::Command::new("protoc")
+            .arg("--version")
+            .output()
+            .is_err()
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().map_err(std::io::Error::other)?;
+        std::env::set_var("PROTOC", protoc);
+    }
+    let mut config = Config::new();
+    config.out_dir("src");
+    #[cfg(feature = "protojson")]
+    let descriptor_path =
+        std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("license_protocol.bin");
+    #[cfg(feature = "protojson")]
+    config.file_descriptor_set_path(&descriptor_path);
+    config.compile_protos(&["src/license_protocol.proto"], &["src/"])?;
+    #[cfg(feature = "protojson")]
+    pbjson_build::Builder::new()
+        .register_descriptors(&std::fs::read(descriptor_path)?)?
+        .build(&[".license_protocol"])?;
     Ok(())
 }
@@ -0,0 +1,225 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    profiles::{ClientIdentity, LicenseHttpRequest, LicenseProfile},
+    Device, KeyContainer, Session,
+};
+use http::StatusCode;
+use native_tls::{Identity, TlsConnector};
+use std::{io::Read, sync::Arc};
+use ureq::{Agent, AgentBuilder, Proxy};
+
+/// A synchronous license client for tools and scripts without an async runtime.
+pub struct BlockingClient {
+    agent: Agent,
+    profile: LicenseProfile,
+}
+
+impl BlockingClient {
+    /// A client for the license server described by `profile`, fails if its proxy or client
+    /// certificate is invalid.
+    pub fn new(profile: LicenseProfile) -> error::Result<BlockingClient> {
+        let mut agent = AgentBuilder::new();
+        if let Some(proxy) = &profile.proxy {
+            agent = agent.proxy(Proxy::new(proxy).map_err(|error| profile.invalid_proxy(error))?);
+        }
+        if let Some(identity) = &profile.identity {
+            let identity = Identity::from_pkcs8(&identity.certificate_chain, &identity.private_key)
+                .map_err(ClientIdentity::invalid)?;
+            let connector = TlsConnector::builder()
+                .identity(identity)
+                .build()
+                .map_err(ClientIdentity::invalid)?;
+            agent = agent.tls_connector(Arc::new(connector));
+        }
+        Ok(BlockingClient {
+            agent: agent.build(),
+            profile,
+        })
+    }
+
+    pub fn profile(&self) -> &LicenseProfile {
+        &self.profile
+    }
+
+    /// Requests the signed service certificate of the license server, unless a valid one is
+    /// cached.
+    pub fn service_certificate(&self) -> error::Result<Vec<u8>> {
+        if let Some(certificate) = self.profile.cached_service_certificate() {
+            return Ok(certificate);
+        }
+        let request = self.profile.prepare_service_certificate_request()?;
+        let certificate = self.send_checked(request)?;
+        self.profile.cache_service_certificate(&certificate);
+        Ok(certificate)
+    }
+
+    /// Sends `challenge` in the request envelope and extracts the license from the response.
+    pub fn exchange(&self, challenge: &[u8]) -> error::Result<Vec<u8>> {
+        self.send_checked(self.profile.prepare(challenge)?)
+    }
+
+    fn send_checked(&self, request: LicenseHttpRequest) -> error::Result<Vec<u8>> {
+        self.profile.check_circuit()?;
+        let license = self.send(request);
+        self.profile.record(&license);
+        license
+    }
+
+    fn send(&self, request: LicenseHttpRequest) -> error::Result<Vec<u8>> {
+        let request_error = |message: String| Error::Request {
+            message,
+            status: None,
+            url: request.url.clone(),
+        };
+        let mut http_request = self.agent.post(&request.url);
+        for (name, value) in &request.headers {
+            http_request = http_request.set(name, value);
+        }
+        let response = match http_request.send_bytes(&request.body) {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(error) => return Err(request_error(format!("Request failed: {error}"))),
+        };
+        let status = StatusCode::from_u16(response.status())
+            .map_err(|error| request_error(format!("Invalid response status: {error}")))?;
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|error| request_error(format!("Failed to read the response: {error}")))?;
+        self.profile.finish(&request.url, status, &body)
+    }
+
+    /// Runs a whole license exchange for `pssh` with the service certificate of the server
+    /// and returns the content keys.
+    pub fn acquire_keys(&self, device: &Device, pssh: Vec<u8>) -> error::Result<Vec<KeyContainer>> {
+        let mut session = Session::new();
+        session.set_service_certificate_from_message(self.service_certificate()?)?;
+        let challenge = session.create_license_request(device, pssh)?;
+        let license = self.exchange(&challenge)?;
+        session.parse_license(device, license)?;
+        Ok(session.keys().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        breaker::CircuitBreaker,
+        envelope::FormRequest,
+        profiles::{serve_once, test_certificate},
+    };
+
+    #[test]
+    fn exchange() {
+        let (url, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nlicense");
+        let client = BlockingClient::new(
+            LicenseProfile::new(&url).with_request(FormRequest::new("challenge").base64(true)),
+        )
+        .unwrap();
+        assert_eq!(client.exchange(&[1, 2, 3]).unwrap(), b"license");
+        assert_eq!(server.join().unwrap().body, b"challenge=AQID");
+
+        let (url, server) = serve_once(
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnope",
+        );
+        let error = BlockingClient::new(LicenseProfile::new(&url))
+            .unwrap()
+            .exchange(&[1, 2, 3])
+            .err()
+            .unwrap();
+        assert!(matches!(
+            error,
+            Error::Request {
+                status: Some(StatusCode::INTERNAL_SERVER_ERROR),
+                ..
+            }
+        ));
+        assert!(error.to_string().contains("nope"));
+        assert_eq!(server.join().unwrap().body, [1, 2, 3]);
+
+        let (url, server) = serve_once(
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        let client = BlockingClient::new(
+            LicenseProfile::new(&url).with_circuit_breaker(Arc::new(CircuitBreaker::new(1, 60))),
+        )
+        .unwrap();
+        assert!(client.exchange(&[1, 2, 3]).is_err());
+        server.join().unwrap();
+        assert!(matches!(
+            client.exchange(&[1, 2, 3]),
+            Err(Error::CircuitOpen { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_service_certificate() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 200 OK\r\nContent-Length: 15\r\nConnection: close\r\n\r\n<html></html>\r\n",
+        );
+        let device = crate::test_device();
+        let result = BlockingClient::new(LicenseProfile::new(&url))
+            .unwrap()
+            .acquire_keys(&device, vec![]);
+        assert!(matches!(result, Err(Error::Input { .. })));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn proxy() {
+        let (proxy, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nlicense");
+        let proxy = proxy.trim_end_matches("/license");
+        let client =
+            BlockingClient::new(LicenseProfile::new("http://license.invalid/").with_proxy(proxy))
+                .unwrap();
+        assert_eq!(client.exchange(&[1, 2, 3]).unwrap(), b"license");
+        let received = server.join().unwrap();
+        assert!(received
+            .head
+            .starts_with("POST http://license.invalid/ HTTP/1.1"));
+        assert_eq!(received.body, [1, 2, 3]);
+
+        assert!(matches!(
+            BlockingClient::new(LicenseProfile::new(proxy).with_proxy("ftp://proxy.invalid")),
+            Err(Error::Input { .. })
+        ));
+    }
+
+    #[test]
+    fn client_identity() {
+        let (certificate, private_key) = test_certificate();
+        let identity = ClientIdentity::from_pem(&certificate, &private_key).unwrap();
+        assert!(BlockingClient::new(
+            LicenseProfile::new("https://license.example/").with_identity(identity)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn hooks() {
+        let (url, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nlicense");
+        let client = BlockingClient::new(
+            LicenseProfile::new(&url)
+                .with_header("X-Cr-Video-Token", "token")
+                .with_hook(|request| {
+                    request.add_query("playbackGuid", "guid");
+                    request.add_cookie("etp_rt", "refresh");
+                    Ok(())
+                }),
+        )
+        .unwrap();
+        assert_eq!(client.exchange(&[1, 2, 3]).unwrap(), b"license");
+        let head = server.join().unwrap().head.to_lowercase();
+        assert!(head.starts_with("post /license?playbackguid=guid http/1.1"));
+        assert!(head.contains("\r\nx-cr-video-token: token\r\n"));
+        assert!(head.contains("\r\ncookie: etp_rt=refresh\r\n"));
+    }
+}
@@ -0,0 +1,213 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    clock::{Clock, SystemClock},
+    error::{self, Error},
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Failure bookkeeping of one license endpoint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EndpointHealth {
+    pub consecutive_failures: u32,
+    /// Requests are refused until this time (seconds since the unix epoch).
+    pub open_until: Option<u64>,
+    /// Start of the single request let through after the cooldown (half-open), other
+    /// requests are refused until its outcome is recorded.
+    pub probe_started: Option<u64>,
+}
+
+/// Stops sending challenges to a license endpoint for `cooldown` seconds after
+/// `failure_threshold` consecutive failures, so broken or hostile servers are not hammered
+/// with device requests. After the cooldown exactly one request is let through, a success
+/// closes the circuit and another failure opens it again right away. A probe whose outcome
+/// is never recorded is given up after another cooldown.
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: u64,
+    clock: Arc<dyn Clock>,
+    endpoints: Mutex<HashMap<String, EndpointHealth>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: u64) -> CircuitBreaker {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            clock: Arc::new(SystemClock),
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> CircuitBreaker {
+        self.clock = clock;
+        self
+    }
+
+    pub fn health(&self, endpoint: &str) -> EndpointHealth {
+        self.endpoints().get(endpoint).copied().unwrap_or_default()
+    }
+
+    /// Fails with [`Error::CircuitOpen`] while requests to `endpoint` are refused. Once the
+    /// cooldown is over the first caller is let through as the probe, so its outcome has to
+    /// be recorded with [`CircuitBreaker::record_success`] or
+    /// [`CircuitBreaker::record_failure`].
+    pub fn check(&self, endpoint: &str) -> error::Result<()> {
+        let now = self.clock.now();
+        let mut endpoints = self.endpoints();
+        let Some(health) = endpoints.get_mut(endpoint) else {
+            return Ok(());
+        };
+        let Some(open_until) = health.open_until else {
+            return Ok(());
+        };
+        // The lock makes taking the probe slot a compare-and-swap, concurrent callers see
+        // the slot taken.
+        let refused_until = match health.probe_started {
+            _ if now < open_until => Some(open_until),
+            Some(probe_started) if now < probe_started + self.cooldown => {
+                Some(probe_started + self.cooldown)
+            }
+            _ => None,
+        };
+        match refused_until {
+            Some(open_until) => Err(Error::CircuitOpen {
+                endpoint: endpoint.to_string(),
+                open_until,
+            }),
+            None => {
+                health.probe_started = Some(now);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn record_success(&self, endpoint: &str) {
+        self.endpoints().remove(endpoint);
+    }
+
+    pub fn record_failure(&self, endpoint: &str) {
+        let now = self.clock.now();
+        let mut endpoints = self.endpoints();
+        let health = endpoints.entry(endpoint.to_string()).or_default();
+        health.consecutive_failures += 1;
+        health.probe_started = None;
+        if health.consecutive_failures >= self.failure_threshold {
+            health.open_until = Some(now + self.cooldown);
+        }
+    }
+
+    /// Runs `request` unless the circuit of `endpoint` is open and records its outcome.
+    pub fn call<T>(
+        &self,
+        endpoint: &str,
+        request: impl FnOnce() -> error::Result<T>,
+    ) -> error::Result<T> {
+        self.check(endpoint)?;
+        let result = request();
+        self.record(endpoint, &result);
+        result
+    }
+
+    pub(crate) fn record<T>(&self, endpoint: &str, result: &error::Result<T>) {
+        match result {
+            Ok(_) => self.record_success(endpoint),
+            Err(_) => self.record_failure(endpoint),
+        }
+    }
+
+    fn endpoints(&self) -> MutexGuard<'_, HashMap<String, EndpointHealth>> {
+        // The map stays consistent even if a thread panicked while holding the lock.
+        self.endpoints
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use std::{sync::Barrier, thread};
+
+    #[test]
+    fn opens_after_repeated_failures() {
+        let clock = Arc::new(ManualClock::new(1000));
+        let breaker = CircuitBreaker::new(2, 60).with_clock(clock.clone());
+        let fail = || -> error::Result<()> {
+            Err(Error::Internal {
+                message: "broken".to_string(),
+            })
+        };
+        assert!(breaker.call("a", fail).is_err());
+        assert!(breaker.check("a").is_ok());
+        assert!(breaker.call("a", fail).is_err());
+        assert!(matches!(
+            breaker.call("a", || Ok(())),
+            Err(Error::CircuitOpen {
+                open_until: 1060,
+                ..
+            })
+        ));
+        assert!(breaker.check("b").is_ok());
+
+        clock.set(1060);
+        assert!(breaker.call("a", fail).is_err());
+        assert!(breaker.check("a").is_err());
+        clock.set(1120);
+        assert!(breaker.call("a", || Ok(())).is_ok());
+        assert_eq!(breaker.health("a"), EndpointHealth::default());
+    }
+
+    #[test]
+    fn single_probe_when_half_open() {
+        let clock = Arc::new(ManualClock::new(1000));
+        let breaker = CircuitBreaker::new(1, 60).with_clock(clock.clone());
+        breaker.record_failure("a");
+        clock.set(1060);
+
+        let barrier = Barrier::new(8);
+        let admitted = thread::scope(|scope| {
+            let callers: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        breaker.check("a").is_ok()
+                    })
+                })
+                .collect();
+            callers
+                .into_iter()
+                .map(|caller| caller.join().unwrap())
+                .filter(|admitted| *admitted)
+                .count()
+        });
+        assert_eq!(admitted, 1);
+        assert_eq!(breaker.health("a").probe_started, Some(1060));
+        assert!(matches!(
+            breaker.check("a"),
+            Err(Error::CircuitOpen {
+                open_until: 1120,
+                ..
+            })
+        ));
+
+        breaker.record_failure("a");
+        assert_eq!(breaker.health("a").open_until, Some(1120));
+        assert!(breaker.check("a").is_err());
+
+        clock.set(1120);
+        assert!(breaker.check("a").is_ok());
+        assert!(breaker.check("a").is_err());
+        // The probe never reported back, the next caller probes after another cooldown.
+        clock.set(1180);
+        assert!(breaker.check("a").is_ok());
+        breaker.record_success("a");
+        assert!(breaker.check("a").is_ok());
+        assert!(breaker.check("a").is_ok());
+    }
+}
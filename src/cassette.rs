@@ -0,0 +1,409 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    clock::FixedClock,
+    decode_signed_license, decrypt_session_key,
+    error::{self, Error},
+    license_protocol::{LicenseRequest, SignedMessage},
+    Device, KeyContainer, Session, SessionState,
+};
+use base64::{engine::general_purpose, Engine as _};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+#[cfg(feature = "json")]
+use std::{fs, path::Path};
+
+/// A recorded license exchange that replays without network access.
+///
+/// Besides the license response, a cassette stores the challenge and the inputs the device
+/// built it from (PSSH, session id, request time and key control nonce) plus the session key
+/// the device got out of the license. [`Cassette::replay`] checks the key derivation and the
+/// license parsing without device credentials, [`Cassette::replay_with_device`] also rebuilds
+/// the challenge. The challenge contains the client identification of the device (unless it
+/// was encrypted to a service certificate), so only commit cassettes of test devices.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub name: String,
+    /// Base64 encoded PSSH box the challenge was created for.
+    pub pssh: String,
+    /// Base64 encoded id of the recorded session.
+    pub session_id: String,
+    /// `request_time` of the challenge, in seconds since the unix epoch.
+    pub request_time: u64,
+    pub key_control_nonce: u32,
+    /// Base64 encoded challenge (signed license request).
+    pub challenge: String,
+    /// Base64 encoded license response.
+    pub license: String,
+    /// Hex encoded session key the device decrypted from the license.
+    pub session_key: String,
+    /// Hex encoded session keys derived during the exchange.
+    pub encryption_key: String,
+    pub server_authentication_key: String,
+    /// Keys the license yielded, as `kid:key`.
+    pub keys: Vec<String>,
+}
+
+impl Cassette {
+    /// Records the exchange of `session`, which has to have created `challenge` for `pssh`
+    /// and parsed `license` with `ldm`.
+    pub fn record(
+        name: &str,
+        ldm: &Device,
+        pssh: &[u8],
+        challenge: &[u8],
+        session: &Session,
+        license: &[u8],
+    ) -> error::Result<Cassette> {
+        let session_keys = session.session_keys().ok_or_else(|| Error::State {
+            message: "Cannot record a session that has not parsed a license.".to_string(),
+        })?;
+        let (signed_request, request) = decode_challenge(challenge)?;
+        if session.raw_license_request.as_deref() != Some(signed_request.msg()) {
+            return Err(Error::Input {
+                message: "The challenge was not created by the recorded session.".to_string(),
+            });
+        }
+        let request_time = u64::try_from(request.request_time()).map_err(|_| Error::Input {
+            message: format!("Invalid request time {}.", request.request_time()),
+        })?;
+        let session_key =
+            decrypt_session_key(ldm.blocking_signer()?, &decode_signed_license(license)?)?;
+        Ok(Cassette {
+            name: name.to_string(),
+            pssh: general_purpose::STANDARD.encode(pssh),
+            session_id: general_purpose::STANDARD.encode(&session.session_id),
+            request_time,
+            key_control_nonce: request.key_control_nonce(),
+            challenge: general_purpose::STANDARD.encode(challenge),
+            license: general_purpose::STANDARD.encode(license),
+            session_key: hex::encode(session_key),
+            encryption_key: hex::encode(&session_keys.encryption_key),
+            server_authentication_key: hex::encode(&session_keys.server_authentication_key),
+            keys: format_keys(session.keys()),
+        })
+    }
+
+    /// Derives the session keys from the recorded challenge and session key and parses the
+    /// recorded license again. Fails if the session keys or the keys of the license differ
+    /// from the recorded ones.
+    pub fn replay(&self) -> error::Result<Session> {
+        let (signed_request, _) =
+            decode_challenge(&self.decode_base64("challenge", &self.challenge)?)?;
+        let mut session = Session::new();
+        session.session_id = self.decode_base64("session_id", &self.session_id)?;
+        session.raw_license_request = Some(signed_request.msg().to_vec());
+        session.state = SessionState::ChallengeSent;
+        let signed_license =
+            session.decode_license(self.decode_base64("license", &self.license)?)?;
+        self.verify(
+            &mut session,
+            signed_license,
+            self.decode_hex("session_key", &self.session_key)?,
+        )?;
+        Ok(session)
+    }
+
+    /// Like [`Cassette::replay`], but builds the challenge with `ldm` from the recorded inputs
+    /// and decrypts the session key of the license with it. Fails if the challenge or the
+    /// session key differ from the recorded ones. The recorded exchange has to have used the
+    /// default session options, an encrypted client id of the challenge is reused as is.
+    pub fn replay_with_device(&self, ldm: &Device) -> error::Result<Session> {
+        let (signed_request, request) =
+            decode_challenge(&self.decode_base64("challenge", &self.challenge)?)?;
+        let mut session = Session::new();
+        session.session_id = self.decode_base64("session_id", &self.session_id)?;
+        session.set_clock(Arc::new(FixedClock(self.request_time)));
+        session.set_key_control_nonce(Some(self.key_control_nonce));
+        session.set_encrypted_client_id(request.encrypted_client_id);
+        session.create_license_request(ldm, self.decode_base64("pssh", &self.pssh)?)?;
+        if session.raw_license_request.as_deref() != Some(signed_request.msg()) {
+            return Err(Error::Internal {
+                message: format!("Cassette {} built a different challenge.", self.name),
+            });
+        }
+        let signed_license =
+            session.decode_license(self.decode_base64("license", &self.license)?)?;
+        let session_key = decrypt_session_key(ldm.blocking_signer()?, &signed_license)?;
+        if hex::encode(&session_key) != self.session_key {
+            return Err(Error::Internal {
+                message: format!("Cassette {} decrypted a different session key.", self.name),
+            });
+        }
+        self.verify(&mut session, signed_license, session_key)?;
+        Ok(session)
+    }
+
+    fn verify(
+        &self,
+        session: &mut Session,
+        signed_license: SignedMessage,
+        session_key: Vec<u8>,
+    ) -> error::Result<()> {
+        let session_keys = session.derive_session_keys(&session_key)?;
+        if hex::encode(&session_keys.encryption_key) != self.encryption_key
+            || hex::encode(&session_keys.server_authentication_key)
+                != self.server_authentication_key
+        {
+            return Err(Error::Internal {
+                message: format!("Cassette {} derived different session keys.", self.name),
+            });
+        }
+        session.load_license_with_keys(signed_license, session_keys)?;
+        let keys = format_keys(session.keys());
+        if keys != self.keys {
+            return Err(Error::Internal {
+                message: format!(
+                    "Cassette {} yielded {keys:?} instead of {:?}.",
+                    self.name, self.keys
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "json")]
+    pub fn load(path: &Path) -> error::Result<Cassette> {
+        let json = fs::read(path).map_err(|error| Error::Input {
+            message: format!("Failed to read {}: {error}", path.display()),
+        })?;
+        Ok(serde_json::from_slice(&json)?)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn save(&self, path: &Path) -> error::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?).map_err(|error| Error::Input {
+            message: format!("Failed to write {}: {error}", path.display()),
+        })
+    }
+
+    fn decode_base64(&self, field: &str, value: &str) -> error::Result<Vec<u8>> {
+        general_purpose::STANDARD
+            .decode(value)
+            .map_err(|error| Error::Input {
+                message: format!("Cassette {} has an invalid {field}: {error}", self.name),
+            })
+    }
+
+    fn decode_hex(&self, field: &str, value: &str) -> error::Result<Vec<u8>> {
+        hex::decode(value).map_err(|error| Error::Input {
+            message: format!("Cassette {} has an invalid {field}: {error}", self.name),
+        })
+    }
+}
+
+fn format_keys(keys: &[KeyContainer]) -> Vec<String> {
+    keys.iter()
+        .map(|key| format!("{}:{}", key.kid, key.key))
+        .collect()
+}
+
+fn decode_challenge(challenge: &[u8]) -> error::Result<(SignedMessage, LicenseRequest)> {
+    let signed_request = SignedMessage::decode(challenge).map_err(|error| Error::Input {
+        message: format!("Provided challenge is not a valid SignedMessage: {error}"),
+    })?;
+    let request = LicenseRequest::decode(signed_request.msg()).map_err(|error| Error::Input {
+        message: format!("Provided challenge is not a valid LicenseRequest: {error}"),
+    })?;
+    Ok((signed_request, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license_protocol::{
+        client_identification::NameValue,
+        license::{key_container::KeyType, KeyContainer as ProtocolKeyContainer},
+        signed_message::MessageType,
+        ClientIdentification, License,
+    };
+    use openssl::{
+        hash::MessageDigest,
+        pkey::{PKey, Private},
+        rsa::{Padding, Rsa},
+        sign::Signer,
+        symm::{encrypt, Cipher},
+    };
+    use std::{fs, path::PathBuf};
+
+    /// PSSH of the `widevine_test` demo content, key id `eb676abbcb345e96bbcf616630f1a3da`.
+    const PSSH: &str = "AAAAW3Bzc2gAAAAA7e+LqXnWSs6jyCfc1R0h7QAAADsIARIQ62dqu8s0Xpa7z2FmMPGj2hoNd2lkZXZpbmVfdGVzdCIQZmtqM2xqYVNkZmFsa3IzaioCSEQyAA==";
+    const KID: [u8; 16] = [
+        0xEB, 0x67, 0x6A, 0xBB, 0xCB, 0x34, 0x5E, 0x96, 0xBB, 0xCF, 0x61, 0x66, 0x30, 0xF1, 0xA3,
+        0xDA,
+    ];
+    const KEY: [u8; 16] = [0x17; 16];
+
+    fn fixture_path(file: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata/cassettes")
+            .join(file)
+    }
+
+    fn test_client_id() -> Vec<u8> {
+        let name_value = |name: &str, value: &str| NameValue {
+            name: Some(name.to_string()),
+            value: Some(value.to_string()),
+        };
+        ClientIdentification {
+            client_info: vec![
+                name_value("company_name", "rust_widevine"),
+                name_value("model_name", "cassette test device"),
+            ],
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    /// One step of the CMAC key derivation of the license protocol, written out separately
+    /// from [`Session`] so the fixture pins the derivation.
+    fn derive(session_key: &[u8], counter: u8, label: &[u8], request: &[u8], bits: u32) -> Vec<u8> {
+        let cmac = PKey::cmac(&Cipher::aes_128_cbc(), session_key).unwrap();
+        let mut signer = Signer::new_without_digest(&cmac).unwrap();
+        signer
+            .update(&[&[counter], label, request, &bits.to_be_bytes()].concat())
+            .unwrap();
+        signer.sign_to_vec().unwrap()
+    }
+
+    /// Answers `challenge` like a license server, with [`KEY`] for [`KID`].
+    fn issue_license(device_key: &Rsa<Private>, challenge: &[u8]) -> Vec<u8> {
+        let (signed_request, _) = decode_challenge(challenge).unwrap();
+        let request = signed_request.msg();
+        let session_key = [0x5A; 16];
+        let encryption_key = derive(&session_key, 1, b"ENCRYPTION\x00", request, 128);
+        let server_authentication_key = [
+            derive(&session_key, 1, b"AUTHENTICATION\x00", request, 512),
+            derive(&session_key, 2, b"AUTHENTICATION\x00", request, 512),
+        ]
+        .concat();
+
+        let iv = [0x03; 16];
+        let license = License {
+            key: vec![ProtocolKeyContainer {
+                id: Some(KID.to_vec()),
+                iv: Some(iv.to_vec()),
+                key: Some(
+                    encrypt(Cipher::aes_128_cbc(), &encryption_key, Some(&iv), &KEY).unwrap(),
+                ),
+                r#type: Some(KeyType::Content.into()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let hmac = PKey::hmac(&server_authentication_key).unwrap();
+        let mut signer = Signer::new(MessageDigest::sha256(), &hmac).unwrap();
+        signer.update(&license).unwrap();
+        let mut encrypted_session_key = vec![0; device_key.size() as usize];
+        let length = device_key
+            .public_encrypt(
+                &session_key,
+                &mut encrypted_session_key,
+                Padding::PKCS1_OAEP,
+            )
+            .unwrap();
+        encrypted_session_key.truncate(length);
+        SignedMessage {
+            r#type: Some(MessageType::License.into()),
+            signature: Some(signer.sign_to_vec().unwrap()),
+            msg: Some(license),
+            session_key: Some(encrypted_session_key),
+            ..Default::default()
+        }
+        .encode_to_vec()
+    }
+
+    fn record_exchange(name: &str, device_key: &Rsa<Private>) -> (Device, Cassette) {
+        let device =
+            Device::from_pem(&device_key.private_key_to_pem().unwrap(), test_client_id()).unwrap();
+        let pssh = general_purpose::STANDARD.decode(PSSH).unwrap();
+        let mut session = Session::new();
+        let challenge = session
+            .create_license_request(&device, pssh.clone())
+            .unwrap();
+        let license = issue_license(device_key, &challenge);
+        assert!(Cassette::record(name, &device, &pssh, &challenge, &session, &license).is_err());
+        session.parse_license(&device, license.clone()).unwrap();
+        let cassette =
+            Cassette::record(name, &device, &pssh, &challenge, &session, &license).unwrap();
+        (device, cassette)
+    }
+
+    #[test]
+    fn record_and_replay() {
+        let (device, cassette) = record_exchange("demo", &Rsa::generate(2048).unwrap());
+        let json = serde_json::to_string(&cassette).unwrap();
+        let cassette: Cassette = serde_json::from_str(&json).unwrap();
+        let expected = vec![KeyContainer::new(KID, &hex::encode(KEY))];
+        assert_eq!(cassette.replay().unwrap().keys(), expected);
+        assert_eq!(
+            cassette.replay_with_device(&device).unwrap().keys(),
+            expected
+        );
+
+        let other_device = Device::from_pem(
+            &Rsa::generate(2048).unwrap().private_key_to_pem().unwrap(),
+            test_client_id(),
+        )
+        .unwrap();
+        assert!(cassette.replay_with_device(&other_device).is_err());
+        let regressed = Cassette {
+            key_control_nonce: cassette.key_control_nonce.wrapping_add(1),
+            ..cassette.clone()
+        };
+        assert!(matches!(
+            regressed.replay_with_device(&device),
+            Err(Error::Internal { .. })
+        ));
+        let regressed = Cassette {
+            session_key: "00".repeat(16),
+            ..cassette.clone()
+        };
+        assert!(matches!(regressed.replay(), Err(Error::Internal { .. })));
+        let regressed = Cassette {
+            keys: vec![format!("{}:{}", hex::encode(KID), "00".repeat(16))],
+            ..cassette
+        };
+        assert!(matches!(regressed.replay(), Err(Error::Internal { .. })));
+    }
+
+    #[test]
+    fn replay_fixture() {
+        let cassette: Cassette =
+            serde_json::from_slice(&fs::read(fixture_path("widevine_test.json")).unwrap()).unwrap();
+        let device = Device::from_pem(
+            &fs::read(fixture_path("device_private_key.pem")).unwrap(),
+            fs::read(fixture_path("device_client_id_blob")).unwrap(),
+        )
+        .unwrap();
+        let expected = vec![KeyContainer::new(KID, &hex::encode(KEY))];
+        assert_eq!(cassette.replay().unwrap().keys(), expected);
+        assert_eq!(
+            cassette.replay_with_device(&device).unwrap().keys(),
+            expected
+        );
+    }
+
+    /// Regenerates the fixture of [`replay_fixture`] with a new throwaway device.
+    #[test]
+    #[ignore]
+    fn record_fixture() {
+        let device_key = Rsa::generate(2048).unwrap();
+        let (_, cassette) = record_exchange("widevine_test", &device_key);
+        fs::create_dir_all(fixture_path("")).unwrap();
+        fs::write(
+            fixture_path("device_private_key.pem"),
+            device_key.private_key_to_pem().unwrap(),
+        )
+        .unwrap();
+        fs::write(fixture_path("device_client_id_blob"), test_client_id()).unwrap();
+        fs::write(
+            fixture_path("widevine_test.json"),
+            serde_json::to_string_pretty(&cassette).unwrap() + "\n",
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,568 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    clock::{Clock, SystemClock},
+    encoding::decode_transport,
+    error::{self, Error},
+    license_protocol::{
+        DrmCertificate, EncryptedClientIdentification, SignedDrmCertificate, SignedMessage,
+    },
+    pssh::Pssh,
+    signer::fingerprint,
+    Device, KeyContainer, Session, SessionState,
+};
+use log::{info, warn};
+use prost::Message;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+};
+
+/// Number of sessions a [`Cdm`] allows to be open at once by default, like pywidevine.
+pub const DEFAULT_MAX_SESSIONS: usize = 16;
+
+/// A license challenge or license parsed by a [`Cdm`] with an enabled audit log.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Seconds since the unix epoch.
+    pub time: u64,
+    /// Fingerprint of the client id of the device.
+    pub device: String,
+    pub session_id: Vec<u8>,
+    /// Fingerprint of the PSSH the license was requested for.
+    pub pssh_hash: Option<String>,
+    /// Set with [`Cdm::set_license_url`].
+    pub license_url: Option<String>,
+    /// Set with [`Session::set_label`].
+    pub labels: BTreeMap<String, String>,
+    pub outcome: AuditOutcome,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuditOutcome {
+    ChallengeCreated,
+    ChallengeFailed { error: String },
+    LicenseParsed { keys: usize },
+    LicenseFailed { error: String },
+}
+
+/// Everything the encrypted client id of a session depends on.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ClientIdKey {
+    serial_number: Vec<u8>,
+    provider_client_token: Option<Vec<u8>>,
+    minimal_client_id: bool,
+}
+
+/// Manages the sessions of a single [`Device`], mirroring pywidevine's `Cdm`.
+///
+/// Like in pywidevine, certificates, PSSHs and licenses may be passed raw or base64
+/// encoded, so tools written against pywidevine translate method by method.
+pub struct Cdm {
+    device: Device,
+    sessions: HashMap<Vec<u8>, Session>,
+    max_sessions: usize,
+    encrypted_client_ids: HashMap<ClientIdKey, EncryptedClientIdentification>,
+    key_control_nonces: HashSet<u32>,
+    clock: Arc<dyn Clock>,
+    license_urls: HashMap<Vec<u8>, String>,
+    pssh_hashes: HashMap<Vec<u8>, String>,
+    audit_log: Option<Vec<AuditEntry>>,
+}
+
+impl Cdm {
+    pub fn new(device: Device) -> Cdm {
+        Cdm {
+            device,
+            sessions: HashMap::new(),
+            max_sessions: DEFAULT_MAX_SESSIONS,
+            encrypted_client_ids: HashMap::new(),
+            key_control_nonces: HashSet::new(),
+            clock: Arc::new(SystemClock),
+            license_urls: HashMap::new(),
+            pssh_hashes: HashMap::new(),
+            audit_log: None,
+        }
+    }
+
+    /// Same as [`Cdm::new`], named like pywidevine's `Cdm.from_device`.
+    pub fn from_device(device: Device) -> Cdm {
+        Cdm::new(device)
+    }
+
+    /// The time source used by all sessions of this CDM.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        for session in self.sessions.values_mut() {
+            session.set_clock(clock.clone());
+        }
+        self.clock = clock;
+    }
+
+    /// Records every challenge and license in an audit log, which is also written to the
+    /// `log` crate at info level. Disabling the audit log discards it.
+    pub fn set_audit_log(&mut self, enabled: bool) {
+        if enabled != self.audit_log.is_some() {
+            self.audit_log = enabled.then(Vec::new);
+        }
+    }
+
+    pub fn audit_log(&self) -> &[AuditEntry] {
+        self.audit_log.as_deref().unwrap_or_default()
+    }
+
+    /// Removes and returns the entries recorded so far.
+    pub fn take_audit_log(&mut self) -> Vec<AuditEntry> {
+        self.audit_log
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// The license server the challenges of a session are sent to, recorded in the audit log.
+    pub fn set_license_url(&mut self, session_id: &[u8], license_url: &str) -> error::Result<()> {
+        self.session(session_id)?;
+        self.license_urls
+            .insert(session_id.to_vec(), license_url.to_string());
+        Ok(())
+    }
+
+    /// Limits the number of open sessions, already open sessions are kept.
+    pub fn set_max_sessions(&mut self, max_sessions: usize) {
+        self.max_sessions = max_sessions;
+    }
+
+    /// The number of sessions that are not closed.
+    pub fn open_sessions(&self) -> usize {
+        self.sessions
+            .values()
+            .filter(|session| session.state() != SessionState::Closed)
+            .count()
+    }
+
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Opens a new session and returns its id, fails with [`Error::TooManySessions`]
+    /// when the session limit is reached.
+    pub fn open(&mut self) -> error::Result<Vec<u8>> {
+        let session_id = Session::new().session_id;
+        self.open_with_id(session_id.clone())?;
+        Ok(session_id)
+    }
+
+    /// Opens a session with an id chosen by the caller, e.g. the id an EME frontend or a
+    /// remote protocol assigned, fails with [`Error::Session`] if the id is already in use.
+    pub fn open_with_id(&mut self, session_id: Vec<u8>) -> error::Result<()> {
+        self.sessions
+            .retain(|_, session| session.state() != SessionState::Closed);
+        self.license_urls
+            .retain(|session_id, _| self.sessions.contains_key(session_id));
+        self.pssh_hashes
+            .retain(|session_id, _| self.sessions.contains_key(session_id));
+        if self.sessions.contains_key(&session_id) {
+            return Err(Error::Session {
+                message: format!("Session {} is already open.", hex::encode(&session_id)),
+            });
+        }
+        if self.sessions.len() >= self.max_sessions {
+            return Err(Error::TooManySessions {
+                limit: self.max_sessions,
+            });
+        }
+        let mut session = Session::new();
+        session.session_id = session_id.clone();
+        session.set_clock(self.clock.clone());
+        self.sessions.insert(session_id, session);
+        Ok(())
+    }
+
+    /// Closes a session, its id becomes invalid and its slot is freed.
+    pub fn close(&mut self, session_id: &[u8]) -> error::Result<()> {
+        let mut session = self
+            .sessions
+            .remove(session_id)
+            .ok_or_else(|| unknown_session(session_id))?;
+        session.close();
+        self.license_urls.remove(session_id);
+        self.pssh_hashes.remove(session_id);
+        Ok(())
+    }
+
+    pub fn session(&self, session_id: &[u8]) -> error::Result<&Session> {
+        self.sessions
+            .get(session_id)
+            .ok_or_else(|| unknown_session(session_id))
+    }
+
+    pub fn session_mut(&mut self, session_id: &[u8]) -> error::Result<&mut Session> {
+        self.sessions
+            .get_mut(session_id)
+            .ok_or_else(|| unknown_session(session_id))
+    }
+
+    /// Installs a service certificate given either as a service certificate response
+    /// (`SignedMessage`) or as a `SignedDrmCertificate` and returns its provider id.
+    pub fn set_service_certificate(
+        &mut self,
+        session_id: &[u8],
+        certificate: impl AsRef<[u8]>,
+    ) -> error::Result<String> {
+        let certificate = certificate.as_ref();
+        let session = self.session_mut(session_id)?;
+        match SignedMessage::try_from(certificate) {
+            Ok(signed_message) => {
+                session.set_service_certificate_from_message(signed_message.encode_to_vec())?
+            }
+            Err(_) => session.set_service_certificate(transport_bytes(certificate, |bytes| {
+                SignedDrmCertificate::try_from(bytes)
+            }))?,
+        }
+        let provider_id = session
+            .service_certificate()
+            .map(|service_certificate| service_certificate.certificate.provider_id().to_string())
+            .unwrap_or_default();
+        Ok(provider_id)
+    }
+
+    /// Creates a license request, the client id encrypted for a service certificate is
+    /// reused for all sessions using the same certificate.
+    pub fn get_license_challenge(
+        &mut self,
+        session_id: &[u8],
+        pssh: impl AsRef<[u8]>,
+    ) -> error::Result<Vec<u8>> {
+        self.audited_challenge(session_id, pssh.as_ref(), true)
+    }
+
+    /// Like [`Cdm::get_license_challenge`], but returns the unsigned license request for a
+    /// signer that is not part of the device, e.g. a remote service answering later. The
+    /// session keeps the request until [`Cdm::attach_signature`] completes the challenge,
+    /// as long as it is not closed and no other request is created in it.
+    pub fn get_unsigned_license_challenge(
+        &mut self,
+        session_id: &[u8],
+        pssh: impl AsRef<[u8]>,
+    ) -> error::Result<Vec<u8>> {
+        self.audited_challenge(session_id, pssh.as_ref(), false)
+    }
+
+    /// Attaches the RSA-PSS (SHA-1) signature of the request returned by
+    /// [`Cdm::get_unsigned_license_challenge`] and returns the challenge.
+    pub fn attach_signature(
+        &mut self,
+        session_id: &[u8],
+        signature: Vec<u8>,
+    ) -> error::Result<Vec<u8>> {
+        self.session_mut(session_id)?
+            .attach_license_request_signature(signature)
+    }
+
+    fn audited_challenge(
+        &mut self,
+        session_id: &[u8],
+        pssh: &[u8],
+        sign: bool,
+    ) -> error::Result<Vec<u8>> {
+        let challenge = self.create_challenge(session_id, pssh, sign);
+        if self.audit_log.is_some() && self.sessions.contains_key(session_id) {
+            let pssh_hash = fingerprint(&transport_bytes(pssh, Pssh::from_bytes));
+            self.pssh_hashes.insert(session_id.to_vec(), pssh_hash);
+            let outcome = match &challenge {
+                Ok(_) => AuditOutcome::ChallengeCreated,
+                Err(error) => AuditOutcome::ChallengeFailed {
+                    error: error.to_string(),
+                },
+            };
+            self.audit(session_id, outcome);
+        }
+        challenge
+    }
+
+    fn create_challenge(
+        &mut self,
+        session_id: &[u8],
+        pssh: &[u8],
+        sign: bool,
+    ) -> error::Result<Vec<u8>> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| unknown_session(session_id))?;
+        let serial_number = session
+            .signed_service_certificate
+            .as_ref()
+            .and_then(|certificate| DrmCertificate::decode(certificate.drm_certificate()).ok())
+            .map(|certificate| certificate.serial_number().to_vec());
+        // The cached client id is only lent to the session for this challenge, so later
+        // changes of the client id settings are picked up by the next one. Encrypted client
+        // ids set by the caller are left alone.
+        let cached = match serial_number {
+            Some(serial_number) if session.encrypted_client_id.is_none() => {
+                let cache_key = ClientIdKey {
+                    serial_number,
+                    provider_client_token: session.provider_client_token.clone(),
+                    minimal_client_id: session.minimal_client_id,
+                };
+                let encrypted_client_id = match self.encrypted_client_ids.get(&cache_key) {
+                    Some(encrypted_client_id) => encrypted_client_id.clone(),
+                    None => {
+                        let encrypted_client_id = session.encrypt_client_id(&self.device)?;
+                        self.encrypted_client_ids
+                            .insert(cache_key, encrypted_client_id.clone());
+                        encrypted_client_id
+                    }
+                };
+                session.set_encrypted_client_id(Some(encrypted_client_id));
+                true
+            }
+            _ => false,
+        };
+        let pssh = if session.raw_init_data {
+            pssh.to_vec()
+        } else {
+            transport_bytes(pssh, Pssh::from_bytes)
+        };
+        let challenge = if sign {
+            session.create_license_request(&self.device, pssh)
+        } else {
+            session.create_unsigned_license_request(&self.device, pssh)
+        };
+        if cached {
+            session.set_encrypted_client_id(None);
+        }
+        let challenge = challenge?;
+        if let Some(nonce) = session.key_control_nonce() {
+            if !self.key_control_nonces.insert(nonce) {
+                warn!("Key control nonce {nonce} was already used by another session.");
+            }
+        }
+        Ok(challenge)
+    }
+
+    pub fn parse_license(
+        &mut self,
+        session_id: &[u8],
+        license: impl AsRef<[u8]>,
+    ) -> error::Result<()> {
+        let session = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| unknown_session(session_id))?;
+        let license = transport_bytes(license.as_ref(), |bytes| SignedMessage::try_from(bytes));
+        let result = session.parse_license(&self.device, license);
+        let outcome = match &result {
+            Ok(_) => AuditOutcome::LicenseParsed {
+                keys: session.keys().len(),
+            },
+            Err(error) => AuditOutcome::LicenseFailed {
+                error: error.to_string(),
+            },
+        };
+        self.audit(session_id, outcome);
+        result.map(|_| ())
+    }
+
+    pub fn get_keys(&self, session_id: &[u8]) -> error::Result<&[KeyContainer]> {
+        Ok(self.session(session_id)?.keys())
+    }
+}
+
+impl Cdm {
+    fn audit(&mut self, session_id: &[u8], outcome: AuditOutcome) {
+        let Some(audit_log) = &mut self.audit_log else {
+            return;
+        };
+        let entry = AuditEntry {
+            time: self.clock.now(),
+            device: fingerprint(&self.device.client_id().encode_to_vec()),
+            session_id: session_id.to_vec(),
+            pssh_hash: self.pssh_hashes.get(session_id).cloned(),
+            license_url: self.license_urls.get(session_id).cloned(),
+            labels: self
+                .sessions
+                .get(session_id)
+                .map(|session| session.labels().clone())
+                .unwrap_or_default(),
+            outcome,
+        };
+        info!("Audit: {entry:?}");
+        audit_log.push(entry);
+    }
+}
+
+/// The raw bytes of a possibly hex or base64 encoded `input`, or `input` itself when no
+/// form is accepted by `decode`, leaving the error to the session.
+fn transport_bytes<T>(input: &[u8], decode: impl Fn(&[u8]) -> error::Result<T>) -> Vec<u8> {
+    decode_transport(input, |bytes| decode(bytes).map(|_| bytes.to_vec()))
+        .unwrap_or_else(|_| input.to_vec())
+}
+
+fn unknown_session(session_id: &[u8]) -> Error {
+    Error::Session {
+        message: format!("Session {} is not open.", hex::encode(session_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kid::Kid,
+        license_protocol::{LicenseRequest, SignedMessage},
+        pssh::Pssh,
+        COMMON_SERVICE_CERTIFICATE,
+    };
+    use base64::{engine::general_purpose, Engine as _};
+
+    #[test]
+    fn session_management() {
+        let device = crate::test_device();
+        let mut cdm = Cdm::new(device);
+        cdm.set_max_sessions(1);
+        let session_id = cdm.open().unwrap();
+        assert!(matches!(
+            cdm.open(),
+            Err(Error::TooManySessions { limit: 1 })
+        ));
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+        assert!(cdm
+            .get_license_challenge(&session_id, pssh.to_bytes())
+            .is_ok());
+        assert!(cdm.get_keys(&session_id).unwrap().is_empty());
+        assert!(cdm.encrypted_client_ids.is_empty());
+        cdm.close(&session_id).unwrap();
+        assert!(matches!(
+            cdm.get_license_challenge(&session_id, pssh.to_bytes()),
+            Err(Error::Session { .. })
+        ));
+        assert!(cdm.close(&session_id).is_err());
+        assert_eq!(cdm.open_sessions(), 0);
+        let session_id = cdm.open().unwrap();
+        cdm.session_mut(&session_id).unwrap().close();
+        assert_eq!(cdm.open_sessions(), 0);
+        cdm.open_with_id(b"eme-session".to_vec()).unwrap();
+        assert!(cdm.session(b"eme-session").is_ok());
+        cdm.set_max_sessions(2);
+        assert!(matches!(
+            cdm.open_with_id(b"eme-session".to_vec()),
+            Err(Error::Session { .. })
+        ));
+    }
+
+    #[test]
+    fn memoized_encrypted_client_id() {
+        let device = crate::test_device();
+        let mut cdm = Cdm::new(device);
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+        let mut encrypted_client_ids = Vec::new();
+        for _ in 0..2 {
+            let session_id = cdm.open().unwrap();
+            let provider_id = cdm
+                .set_service_certificate(
+                    &session_id,
+                    general_purpose::STANDARD.encode(COMMON_SERVICE_CERTIFICATE),
+                )
+                .unwrap();
+            assert_eq!(provider_id, "license.widevine.com");
+            let challenge = cdm
+                .get_license_challenge(&session_id, pssh.to_base64())
+                .unwrap();
+            let signed_message = SignedMessage::decode(challenge.as_slice()).unwrap();
+            let license_request = LicenseRequest::decode(signed_message.msg()).unwrap();
+            encrypted_client_ids.push(license_request.encrypted_client_id.unwrap());
+        }
+        assert_eq!(encrypted_client_ids[0], encrypted_client_ids[1]);
+        assert_eq!(cdm.encrypted_client_ids.len(), 1);
+
+        let session_id = cdm.open().unwrap();
+        cdm.set_service_certificate(&session_id, COMMON_SERVICE_CERTIFICATE)
+            .unwrap();
+        cdm.session_mut(&session_id)
+            .unwrap()
+            .set_minimal_client_id(true);
+        let challenge = cdm.get_license_challenge(&session_id, pssh.to_bytes());
+        let encrypted_client_id = sent_encrypted_client_id(&challenge.unwrap());
+        assert_ne!(encrypted_client_id, encrypted_client_ids[0]);
+        assert_eq!(cdm.encrypted_client_ids.len(), 2);
+
+        cdm.session_mut(&session_id)
+            .unwrap()
+            .set_request_provider_client_token(Some(b"token".to_vec()));
+        let challenge = cdm.get_license_challenge(&session_id, pssh.to_bytes());
+        assert_ne!(
+            sent_encrypted_client_id(&challenge.unwrap()),
+            encrypted_client_id
+        );
+        assert_eq!(cdm.encrypted_client_ids.len(), 3);
+    }
+
+    fn sent_encrypted_client_id(challenge: &[u8]) -> EncryptedClientIdentification {
+        let signed_message = SignedMessage::decode(challenge).unwrap();
+        let license_request = LicenseRequest::decode(signed_message.msg()).unwrap();
+        license_request.encrypted_client_id.unwrap()
+    }
+
+    #[test]
+    fn audit_log() {
+        let device = crate::test_device();
+        let mut cdm = Cdm::new(device);
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+        let session_id = cdm.open().unwrap();
+        cdm.get_license_challenge(&session_id, pssh.to_bytes())
+            .unwrap();
+        assert!(cdm.audit_log().is_empty());
+
+        cdm.set_audit_log(true);
+        cdm.set_license_url(&session_id, "https://license.example/")
+            .unwrap();
+        cdm.session_mut(&session_id).unwrap().set_label("job", "7");
+        cdm.get_license_challenge(&session_id, pssh.to_base64())
+            .unwrap();
+        assert!(cdm.parse_license(&session_id, b"not a license").is_err());
+        let audit_log = cdm.take_audit_log();
+        assert_eq!(audit_log.len(), 2);
+        assert_eq!(audit_log[0].outcome, AuditOutcome::ChallengeCreated);
+        assert_eq!(audit_log[0].pssh_hash, Some(fingerprint(&pssh.to_bytes())));
+        assert_eq!(
+            audit_log[0].labels.get("job").map(String::as_str),
+            Some("7")
+        );
+        assert_eq!(
+            audit_log[1].license_url.as_deref(),
+            Some("https://license.example/")
+        );
+        assert!(matches!(
+            audit_log[1].outcome,
+            AuditOutcome::LicenseFailed { .. }
+        ));
+        assert!(cdm.audit_log().is_empty());
+    }
+
+    #[test]
+    fn detached_signature() {
+        let mut cdm = Cdm::new(crate::test_device());
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+        let session_id = cdm.open().unwrap();
+        assert!(cdm.attach_signature(&session_id, vec![0x01; 256]).is_err());
+        let license_request = cdm
+            .get_unsigned_license_challenge(&session_id, pssh.to_bytes())
+            .unwrap();
+        assert!(LicenseRequest::decode(license_request.as_slice()).is_ok());
+
+        let remote_signer = Device::from_pem(crate::test_private_key(), Vec::new()).unwrap();
+        let signature = remote_signer
+            .blocking_signer()
+            .unwrap()
+            .sign(&license_request)
+            .unwrap();
+        let challenge = cdm.attach_signature(&session_id, signature).unwrap();
+        let signed_message = SignedMessage::decode(challenge.as_slice()).unwrap();
+        assert_eq!(signed_message.msg(), license_request);
+        assert_eq!(
+            cdm.session(&session_id).unwrap().state(),
+            SessionState::ChallengeSent
+        );
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    clock::{Clock, SystemClock},
+    error::{self, Error},
+    license_protocol::SignedMessage,
+    Session,
+};
+use log::warn;
+use openssl::sha::sha256;
+use prost::Message;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Keeps fetched service certificates (the `SignedMessage` a license server answers a
+/// service certificate request with) in a directory, one file per license URL, so repeated
+/// runs skip the certificate round trip. Certificates are verified again when loaded and
+/// dropped once they fail verification or expire.
+#[derive(Clone)]
+pub struct ServiceCertificateCache {
+    directory: PathBuf,
+    clock: Arc<dyn Clock>,
+}
+
+impl ServiceCertificateCache {
+    pub fn new(directory: impl Into<PathBuf>) -> ServiceCertificateCache {
+        ServiceCertificateCache {
+            directory: directory.into(),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> ServiceCertificateCache {
+        self.clock = clock;
+        self
+    }
+
+    /// The cached certificate of `license_url`, `None` if there is no valid one.
+    pub fn load(&self, license_url: &str) -> Option<Vec<u8>> {
+        let path = self.path(license_url);
+        let certificate = fs::read(&path).ok()?;
+        if let Err(error) = self.validate(&certificate) {
+            warn!(
+                "Dropping cached service certificate {}: {error}",
+                path.display()
+            );
+            let _ = fs::remove_file(&path);
+            return None;
+        }
+        Some(certificate)
+    }
+
+    /// Stores `certificate` for `license_url` if it is valid.
+    pub fn store(&self, license_url: &str, certificate: &[u8]) -> error::Result<()> {
+        self.validate(certificate)?;
+        fs::create_dir_all(&self.directory)
+            .map_err(|error| io_error("create", &self.directory, error))?;
+        let path = self.path(license_url);
+        fs::write(&path, certificate).map_err(|error| io_error("write", &path, error))
+    }
+
+    /// The cached certificate of `license_url`, or the one returned by `fetch`, which is
+    /// then cached.
+    pub fn get_or_fetch(
+        &self,
+        license_url: &str,
+        fetch: impl FnOnce() -> error::Result<Vec<u8>>,
+    ) -> error::Result<Vec<u8>> {
+        if let Some(certificate) = self.load(license_url) {
+            return Ok(certificate);
+        }
+        let certificate = fetch()?;
+        self.store_fetched(license_url, &certificate);
+        Ok(certificate)
+    }
+
+    /// Like [`ServiceCertificateCache::store`], failures are only logged because the
+    /// certificate itself was fetched fine.
+    pub(crate) fn store_fetched(&self, license_url: &str, certificate: &[u8]) {
+        if let Err(error) = self.store(license_url, certificate) {
+            warn!("Failed to cache the service certificate of {license_url}: {error}");
+        }
+    }
+
+    fn path(&self, license_url: &str) -> PathBuf {
+        let name = hex::encode(sha256(license_url.as_bytes()));
+        self.directory.join(format!("{name}.bin"))
+    }
+
+    fn validate(&self, certificate: &[u8]) -> error::Result<()> {
+        let signed_message = SignedMessage::decode(certificate).map_err(|error| Error::Input {
+            message: format!("Provided data is not a valid SignedMessage: {error}"),
+        })?;
+        let mut session = Session::new();
+        session.set_clock(self.clock.clone());
+        session.set_service_certificate(signed_message.msg().to_vec())
+    }
+}
+
+fn io_error(operation: &str, path: &Path, error: std::io::Error) -> Error {
+    Error::Internal {
+        message: format!("Failed to {operation} {}: {error}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::FixedClock, COMMON_SERVICE_CERTIFICATE};
+    use std::env;
+
+    #[test]
+    fn persist_and_verify() {
+        let directory = env::temp_dir().join(format!(
+            "rust_widevine_certificate_cache_{}",
+            std::process::id()
+        ));
+        let cache = ServiceCertificateCache::new(&directory)
+            .with_clock(Arc::new(FixedClock(1_700_000_000)));
+        let url = "https://license.example/";
+        assert!(cache.store(url, b"not a certificate").is_err());
+        assert!(cache.load(url).is_none());
+
+        let fetched = cache
+            .get_or_fetch(url, || Ok(COMMON_SERVICE_CERTIFICATE.to_vec()))
+            .unwrap();
+        assert_eq!(fetched, COMMON_SERVICE_CERTIFICATE);
+        let cached = cache
+            .get_or_fetch(url, || panic!("the certificate was not cached"))
+            .unwrap();
+        assert_eq!(cached, COMMON_SERVICE_CERTIFICATE);
+
+        let path = cache.path(url);
+        let mut tampered = COMMON_SERVICE_CERTIFICATE.to_vec();
+        *tampered.last_mut().unwrap() ^= 0xff;
+        fs::write(&path, tampered).unwrap();
+        assert!(cache.load(url).is_none());
+        assert!(!path.exists());
+
+        fs::write(&path, b"not a directory").unwrap();
+        let blocked = ServiceCertificateCache::new(path.join("cache"))
+            .with_clock(Arc::new(FixedClock(1_700_000_000)));
+        match blocked.store(url, &COMMON_SERVICE_CERTIFICATE) {
+            Err(Error::Internal { message }) => assert!(message.starts_with("Failed to create")),
+            result => panic!("expected an internal error, got {result:?}"),
+        }
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error, LicenseStatus},
+    merge_keys,
+    profiles::ClientIdentity,
+    Device, KeyContainer, Session,
+};
+use base64::{engine::general_purpose, Engine as _};
+use futures_util::{stream, StreamExt};
+use reqwest::{Client, Identity, Proxy};
+
+pub use crate::profiles::{LicenseHttpRequest, LicenseProfile, RequestHook};
+
+/// Public Widevine demo license server (Shaka Player's proxy), which accepts any valid device.
+pub const DEMO_LICENSE_URL: &str = "https://cwip-shaka-proxy.appspot.com/no_auth";
+
+/// PSSH of the `widevine_test` demo content served by [`DEMO_LICENSE_URL`].
+pub const DEMO_PSSH: &str = "AAAAW3Bzc2gAAAAA7e+LqXnWSs6jyCfc1R0h7QAAADsIARIQ62dqu8s0Xpa7z2FmMPGj2hoNd2lkZXZpbmVfdGVzdCIQZmtqM2xqYVNkZmFsa3IzaioCSEQyAA==";
+
+impl LicenseProfile {
+    /// The HTTP client requests of this profile are sent with, using its proxy and client
+    /// certificate.
+    fn http_client(&self) -> error::Result<Client> {
+        let mut builder = Client::builder();
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy).map_err(|error| self.invalid_proxy(error))?);
+        }
+        if let Some(identity) = &self.identity {
+            let pem = [&identity.private_key[..], &identity.certificate_chain[..]].concat();
+            builder = builder.identity(Identity::from_pem(&pem).map_err(ClientIdentity::invalid)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    /// The service certificate of the license server, from the cache if possible.
+    async fn service_certificate(&self, client: &Client) -> error::Result<Vec<u8>> {
+        if let Some(certificate) = self.cached_service_certificate() {
+            return Ok(certificate);
+        }
+        let request = self.prepare_service_certificate_request()?;
+        let certificate = self.exchange(client, request).await?;
+        self.cache_service_certificate(&certificate);
+        Ok(certificate)
+    }
+
+    /// Sends `request` and extracts the license from the response.
+    async fn exchange(
+        &self,
+        client: &Client,
+        request: LicenseHttpRequest,
+    ) -> error::Result<Vec<u8>> {
+        self.check_circuit()?;
+        let license = self.send(client, request).await;
+        self.record(&license);
+        license
+    }
+
+    async fn send(&self, client: &Client, request: LicenseHttpRequest) -> error::Result<Vec<u8>> {
+        let mut builder = client.post(&request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder.body(request.body).send().await?;
+        let status = response.status();
+        let body = response.bytes().await?;
+        self.finish(&request.url, status, &body)
+    }
+}
+
+/// Outcome of [`Device::acquire_keys_for`].
+#[derive(Debug, Default)]
+pub struct Acquisition {
+    /// Keys of all licenses, merged with [`merge_keys`].
+    pub keys: Vec<KeyContainer>,
+    /// Failures with the index of their PSSH in the list.
+    pub errors: Vec<(usize, Error)>,
+}
+
+impl Acquisition {
+    fn from_results(mut results: Vec<(usize, error::Result<Vec<KeyContainer>>)>) -> Acquisition {
+        results.sort_by_key(|(index, _)| *index);
+        let mut keys = Vec::new();
+        let mut errors = Vec::new();
+        for (index, result) in results {
+            match result {
+                Ok(license_keys) => keys.extend(license_keys),
+                Err(error) => errors.push((index, error)),
+            }
+        }
+        Acquisition {
+            keys: merge_keys(keys),
+            errors,
+        }
+    }
+}
+
+/// Outcome of [`Device::self_test`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceStatus {
+    /// The license server issued a license with `keys` keys.
+    Accepted { keys: usize },
+    /// The device certificate has been revoked.
+    Revoked,
+    /// The device could not create a valid request or the server rejected its credentials.
+    Malformed { reason: String },
+}
+
+impl Device {
+    /// Requests a license for the demo content from [`DEMO_LICENSE_URL`] and reports whether
+    /// the device is accepted. Network and server failures are returned as errors.
+    pub async fn self_test(&self) -> error::Result<DeviceStatus> {
+        let pssh = general_purpose::STANDARD.decode(DEMO_PSSH).unwrap();
+        self.self_test_with(&LicenseProfile::new(DEMO_LICENSE_URL), pssh)
+            .await
+    }
+
+    /// Like [`Device::self_test`], against the license server described by `profile`. The
+    /// requests go through the envelopes, hooks and circuit breaker of the profile like any
+    /// other exchange.
+    pub async fn self_test_with(
+        &self,
+        profile: &LicenseProfile,
+        pssh: Vec<u8>,
+    ) -> error::Result<DeviceStatus> {
+        let client = profile.http_client()?;
+        let mut session = Session::new();
+        session
+            .set_service_certificate_from_message(profile.service_certificate(&client).await?)?;
+        let challenge = match session.create_license_request_async(self, pssh).await {
+            Ok(challenge) => challenge,
+            Err(error) => {
+                return Ok(DeviceStatus::Malformed {
+                    reason: error.to_string(),
+                })
+            }
+        };
+        let license = match profile
+            .exchange(&client, profile.prepare(&challenge)?)
+            .await
+        {
+            Ok(license) => license,
+            Err(Error::Request {
+                message,
+                status: Some(status),
+                ..
+            }) if status.is_client_error() || status.is_server_error() => {
+                return Ok(DeviceStatus::Malformed { reason: message })
+            }
+            Err(error) => return Err(error),
+        };
+        match session.parse_license_async(self, license).await {
+            Ok(_) => Ok(DeviceStatus::Accepted {
+                keys: session.keys().len(),
+            }),
+            Err(Error::License {
+                status: LicenseStatus::DeviceRevoked,
+            }) => Ok(DeviceStatus::Revoked),
+            Err(Error::License {
+                status: LicenseStatus::InvalidDeviceCertificate,
+            }) => Ok(DeviceStatus::Malformed {
+                reason: LicenseStatus::InvalidDeviceCertificate.to_string(),
+            }),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Acquires the licenses of `pssh_list` from `license_url`, with at most `concurrency`
+    /// license requests in flight. The service certificate is requested once and shared by
+    /// all sessions, failing to get it fails the whole acquisition.
+    pub async fn acquire_keys_for(
+        &self,
+        pssh_list: &[Vec<u8>],
+        license_url: &str,
+        concurrency: usize,
+    ) -> error::Result<Acquisition> {
+        self.acquire_keys_with(pssh_list, &LicenseProfile::new(license_url), concurrency)
+            .await
+    }
+
+    /// Like [`Device::acquire_keys_for`], for a license server described by `profile`.
+    pub async fn acquire_keys_with(
+        &self,
+        pssh_list: &[Vec<u8>],
+        profile: &LicenseProfile,
+        concurrency: usize,
+    ) -> error::Result<Acquisition> {
+        let client = profile.http_client()?;
+        let service_certificate = profile.service_certificate(&client).await?;
+        let results: Vec<(usize, error::Result<Vec<KeyContainer>>)> =
+            stream::iter(pssh_list.iter().enumerate())
+                .map(|(index, pssh)| {
+                    let client = &client;
+                    let service_certificate = &service_certificate;
+                    async move {
+                        let keys = self
+                            .acquire_keys(client, profile, service_certificate, pssh)
+                            .await;
+                        (index, keys)
+                    }
+                })
+                .buffer_unordered(concurrency.max(1))
+                .collect()
+                .await;
+        Ok(Acquisition::from_results(results))
+    }
+
+    async fn acquire_keys(
+        &self,
+        client: &Client,
+        profile: &LicenseProfile,
+        service_certificate: &[u8],
+        pssh: &[u8],
+    ) -> error::Result<Vec<KeyContainer>> {
+        let mut session = Session::new();
+        session.set_service_certificate_from_message(service_certificate.to_vec())?;
+        let challenge = session
+            .create_license_request_async(self, pssh.to_vec())
+            .await?;
+        let license = profile
+            .exchange(client, profile.prepare(&challenge)?)
+            .await?;
+        session.parse_license_async(self, license).await?;
+        Ok(session.keys().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profiles::{serve_once, test_certificate};
+    use reqwest::StatusCode;
+    use std::fs;
+
+    #[tokio::test]
+    #[ignore]
+    async fn self_test() {
+        let device = Device::from_pem(
+            &fs::read("security/device_private_key").unwrap(),
+            fs::read("security/device_client_id_blob").unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            device.self_test().await.unwrap(),
+            DeviceStatus::Accepted { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn self_test_with_profile() {
+        let (url, server) = serve_once(
+            "HTTP/1.1 403 Forbidden\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnope",
+        );
+        let device = crate::test_device();
+        let profile = LicenseProfile::new(&url).with_header("X-Test", "self-test");
+        let pssh = general_purpose::STANDARD.decode(DEMO_PSSH).unwrap();
+        assert!(matches!(
+            device.self_test_with(&profile, pssh).await,
+            Err(Error::Request {
+                status: Some(StatusCode::FORBIDDEN),
+                ..
+            })
+        ));
+        let received = server.join().unwrap();
+        assert!(received
+            .head
+            .to_lowercase()
+            .contains("\r\nx-test: self-test\r\n"));
+    }
+
+    #[test]
+    fn merged_acquisition() {
+        let results = vec![
+            (2, Ok(vec![KeyContainer::new([0xFF; 16], "01")])),
+            (
+                1,
+                Err(Error::Request {
+                    message: "The license server rejected the request: nope".to_string(),
+                    status: Some(StatusCode::FORBIDDEN),
+                    url: DEMO_LICENSE_URL.to_string(),
+                }),
+            ),
+            (0, Ok(vec![KeyContainer::new([0x0A; 16], "02")])),
+            (3, Ok(vec![KeyContainer::new([0x0A; 16], "02")])),
+        ];
+        let acquisition = Acquisition::from_results(results);
+        assert_eq!(
+            acquisition.keys,
+            vec![
+                KeyContainer::new([0x0A; 16], "02"),
+                KeyContainer::new([0xFF; 16], "01")
+            ]
+        );
+        assert_eq!(acquisition.errors.len(), 1);
+        assert!(matches!(
+            acquisition.errors[0],
+            (
+                1,
+                Error::Request {
+                    status: Some(StatusCode::FORBIDDEN),
+                    ..
+                }
+            )
+        ));
+    }
+
+    #[tokio::test]
+    async fn proxy() {
+        let (proxy, server) =
+            serve_once("HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nlicense");
+        let profile = LicenseProfile::new("http://license.invalid/license")
+            .with_proxy(proxy.trim_end_matches("/license"));
+        let license = profile
+            .exchange(
+                &profile.http_client().unwrap(),
+                profile.prepare(&[1, 2, 3]).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(license, b"license");
+        let received = server.join().unwrap();
+        assert!(received
+            .head
+            .starts_with("POST http://license.invalid/license HTTP/1.1"));
+        assert_eq!(received.body, [1, 2, 3]);
+
+        let profile = LicenseProfile::new(DEMO_LICENSE_URL).with_proxy("not a proxy");
+        assert!(matches!(profile.http_client(), Err(Error::Input { .. })));
+    }
+
+    #[test]
+    fn client_identity() {
+        let (certificate, private_key) = test_certificate();
+        let identity = ClientIdentity::from_pem(&certificate, &private_key).unwrap();
+        assert!(LicenseProfile::new(DEMO_LICENSE_URL)
+            .with_identity(identity)
+            .http_client()
+            .is_ok());
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn acquire_keys_for() {
+        let device = Device::from_pem(
+            &fs::read("security/device_private_key").unwrap(),
+            fs::read("security/device_client_id_blob").unwrap(),
+        )
+        .unwrap();
+        let pssh = general_purpose::STANDARD.decode(DEMO_PSSH).unwrap();
+        let acquisition = device
+            .acquire_keys_for(&[pssh.clone(), pssh, vec![0x00]], DEMO_LICENSE_URL, 2)
+            .await
+            .unwrap();
+        assert!(!acquisition.keys.is_empty());
+        assert_eq!(acquisition.errors.len(), 1);
+        assert_eq!(acquisition.errors[0].0, 2);
+    }
+}
@@ -0,0 +1,199 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    signer, Device,
+};
+use base64::{engine::general_purpose, Engine as _};
+use openssl::{pkcs12::Pkcs12, x509::X509};
+use std::{
+    env, fmt, fs,
+    path::{Path, PathBuf},
+};
+
+/// Where the secrets of a [`Device`] are loaded from.
+pub trait CredentialSource {
+    /// The PEM encoded device private key.
+    fn private_key(&self) -> error::Result<Vec<u8>>;
+
+    /// The serialized `ClientIdentification` (client id blob).
+    fn client_id(&self) -> error::Result<Vec<u8>>;
+}
+
+/// Credentials stored in two files, like `security/device_private_key` and
+/// `security/device_client_id_blob`.
+#[derive(Clone, Debug)]
+pub struct FileCredentials {
+    pub private_key: PathBuf,
+    pub client_id: PathBuf,
+}
+
+impl CredentialSource for FileCredentials {
+    fn private_key(&self) -> error::Result<Vec<u8>> {
+        read_file(&self.private_key)
+    }
+
+    fn client_id(&self) -> error::Result<Vec<u8>> {
+        read_file(&self.client_id)
+    }
+}
+
+/// Credentials stored in environment variables, the private key as PEM and the
+/// client id blob as base64.
+#[derive(Clone, Debug)]
+pub struct EnvCredentials {
+    pub private_key: String,
+    pub client_id: String,
+}
+
+impl CredentialSource for EnvCredentials {
+    fn private_key(&self) -> error::Result<Vec<u8>> {
+        Ok(read_var(&self.private_key)?.into_bytes())
+    }
+
+    fn client_id(&self) -> error::Result<Vec<u8>> {
+        general_purpose::STANDARD
+            .decode(read_var(&self.client_id)?.trim())
+            .map_err(|error| Error::Input {
+                message: format!("{} is not valid base64: {error}", self.client_id),
+            })
+    }
+}
+
+/// A device private key (and possibly its certificate) in a PKCS#12 container, with the
+/// client id blob in a separate file.
+#[derive(Clone)]
+pub struct Pkcs12Credentials {
+    pub container: PathBuf,
+    pub password: String,
+    pub client_id: PathBuf,
+}
+
+impl Pkcs12Credentials {
+    /// The certificate bundled with the private key, if the container has one.
+    pub fn certificate(&self) -> error::Result<Option<X509>> {
+        Ok(self.parse()?.cert)
+    }
+
+    fn parse(&self) -> error::Result<openssl::pkcs12::ParsedPkcs12_2> {
+        let container = read_file(&self.container)?;
+        Pkcs12::from_der(&container)
+            .and_then(|pkcs12| pkcs12.parse2(&self.password))
+            .map_err(|stack| Error::OpenSSL {
+                message: format!(
+                    "Failed to open the PKCS#12 container {}",
+                    self.container.display()
+                ),
+                stack,
+            })
+    }
+}
+
+impl fmt::Debug for Pkcs12Credentials {
+    /// Redacts the password, so credentials can be logged safely.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pkcs12Credentials")
+            .field("container", &self.container)
+            .field("password", &"<redacted>")
+            .field("client_id", &self.client_id)
+            .finish()
+    }
+}
+
+impl CredentialSource for Pkcs12Credentials {
+    fn private_key(&self) -> error::Result<Vec<u8>> {
+        let private_key = self.parse()?.pkey.ok_or_else(|| Error::Input {
+            message: format!("{} contains no private key.", self.container.display()),
+        })?;
+        private_key
+            .private_key_to_pem_pkcs8()
+            .map_err(|stack| Error::OpenSSL {
+                message: "Failed to export the device private key".to_string(),
+                stack,
+            })
+    }
+
+    fn client_id(&self) -> error::Result<Vec<u8>> {
+        read_file(&self.client_id)
+    }
+}
+
+impl Device {
+    pub fn from_source(source: &dyn CredentialSource) -> error::Result<Device> {
+        let signer = signer::from_pem(&source.private_key()?)?;
+        Device::with_signer(signer, source.client_id()?)
+    }
+}
+
+fn read_file(path: &Path) -> error::Result<Vec<u8>> {
+    fs::read(path).map_err(|error| Error::Input {
+        message: format!("Failed to read {}: {error}", path.display()),
+    })
+}
+
+fn read_var(name: &str) -> error::Result<String> {
+    env::var(name).map_err(|error| Error::Input {
+        message: format!("Failed to read environment variable {name}: {error}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::license_protocol::ClientIdentification;
+    use openssl::{pkey::PKey, rsa::Rsa};
+    use prost::Message;
+
+    #[test]
+    fn file_credentials() {
+        let directory = env::temp_dir().join(format!("rust_widevine_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let credentials = FileCredentials {
+            private_key: directory.join("device_private_key"),
+            client_id: directory.join("device_client_id_blob"),
+        };
+        assert!(Device::from_source(&credentials).is_err());
+        let client_id = ClientIdentification {
+            vmp_data: Some(vec![1, 2, 3]),
+            ..Default::default()
+        };
+        fs::write(&credentials.private_key, crate::test_private_key()).unwrap();
+        fs::write(&credentials.client_id, client_id.encode_to_vec()).unwrap();
+        let device = Device::from_source(&credentials).unwrap();
+        assert_eq!(device.vmp_data(), Some([1, 2, 3].as_slice()));
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn pkcs12_credentials() {
+        let directory = env::temp_dir().join(format!("rust_widevine_p12_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let credentials = Pkcs12Credentials {
+            container: directory.join("device.p12"),
+            password: "secret".to_string(),
+            client_id: directory.join("device_client_id_blob"),
+        };
+        let private_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let container = Pkcs12::builder()
+            .name("device")
+            .pkey(&private_key)
+            .build2(&credentials.password)
+            .unwrap();
+        fs::write(&credentials.container, container.to_der().unwrap()).unwrap();
+        fs::write(
+            &credentials.client_id,
+            ClientIdentification::default().encode_to_vec(),
+        )
+        .unwrap();
+        assert!(Device::from_source(&credentials).is_ok());
+        assert!(credentials.certificate().unwrap().is_none());
+        assert!(!format!("{credentials:?}").contains("secret"));
+        let wrong_password = Pkcs12Credentials {
+            password: "wrong".to_string(),
+            ..credentials
+        };
+        assert!(Device::from_source(&wrong_password).is_err());
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
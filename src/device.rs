@@ -0,0 +1,345 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    license_protocol::{
+        client_identification::TokenType, ClientIdentification, DrmCertificate, LicenseRequest,
+        SignedDrmCertificate, SignedMessage,
+    },
+    signer::{self, fingerprint, AsyncDeviceSigner, DeviceSigner},
+};
+use log::info;
+use prost::Message;
+use std::fmt;
+
+/// The identity of a Widevine device: its client identification (including the
+/// VMP data) and the signer holding its private key.
+pub struct Device {
+    signer: SignerBackend,
+    client_id: ClientIdentification,
+}
+
+enum SignerBackend {
+    Blocking(Box<dyn DeviceSigner>),
+    Async(Box<dyn AsyncDeviceSigner>),
+}
+
+impl Device {
+    #[deprecated(note = "panics on invalid input, use `Device::from_pem` instead")]
+    pub fn new(private_key: &[u8], identification_blob: Vec<u8>) -> Device {
+        Device::from_pem(private_key, identification_blob).unwrap()
+    }
+
+    /// Loads a device from its PEM encoded RSA or ECC (feature `experimental-ecc`) private
+    /// key and its client id blob.
+    pub fn from_pem(private_key: &[u8], identification_blob: Vec<u8>) -> error::Result<Device> {
+        Device::with_signer(signer::from_pem(private_key)?, identification_blob)
+    }
+
+    /// Uses `signer` for the private key operations instead of a key loaded into memory.
+    pub fn with_signer(
+        signer: Box<dyn DeviceSigner>,
+        identification_blob: Vec<u8>,
+    ) -> error::Result<Device> {
+        let client_id = decode_client_id(&identification_blob)?;
+        check_device_certificate(&client_id, signer.public_key())?;
+        Ok(Device {
+            signer: SignerBackend::Blocking(signer),
+            client_id,
+        })
+    }
+
+    /// Uses an async `signer`, only [`Session::create_license_request_async`] and
+    /// [`Session::parse_license_async`] can be used with such a device.
+    ///
+    /// [`Session::create_license_request_async`]: crate::Session::create_license_request_async
+    /// [`Session::parse_license_async`]: crate::Session::parse_license_async
+    pub fn with_async_signer(
+        signer: Box<dyn AsyncDeviceSigner>,
+        identification_blob: Vec<u8>,
+    ) -> error::Result<Device> {
+        let client_id = decode_client_id(&identification_blob)?;
+        check_device_certificate(&client_id, None)?;
+        Ok(Device {
+            signer: SignerBackend::Async(signer),
+            client_id,
+        })
+    }
+
+    pub fn client_id(&self) -> &ClientIdentification {
+        &self.client_id
+    }
+
+    /// Serialized VMP (verified media path) data of the client identification.
+    pub fn vmp_data(&self) -> Option<&[u8]> {
+        self.client_id.vmp_data.as_deref()
+    }
+
+    pub(crate) fn blocking_signer(&self) -> error::Result<&dyn DeviceSigner> {
+        match &self.signer {
+            SignerBackend::Blocking(signer) => Ok(signer.as_ref()),
+            SignerBackend::Async(_) => Err(Error::Input {
+                message: "This device uses an async signer, use the async session methods."
+                    .to_string(),
+            }),
+        }
+    }
+
+    pub(crate) async fn sign(&self, message: &[u8]) -> error::Result<Vec<u8>> {
+        match &self.signer {
+            SignerBackend::Blocking(signer) => signer.sign(message),
+            SignerBackend::Async(signer) => signer.sign(message).await,
+        }
+    }
+
+    pub(crate) async fn decrypt(&self, ciphertext: &[u8]) -> error::Result<Vec<u8>> {
+        match &self.signer {
+            SignerBackend::Blocking(signer) => signer.decrypt(ciphertext),
+            SignerBackend::Async(signer) => signer.decrypt(ciphertext).await,
+        }
+    }
+
+    pub(crate) async fn derive_session_key(
+        &self,
+        ephemeral_public_key: &[u8],
+    ) -> error::Result<Vec<u8>> {
+        match &self.signer {
+            SignerBackend::Blocking(signer) => signer.derive_session_key(ephemeral_public_key),
+            SignerBackend::Async(signer) => signer.derive_session_key(ephemeral_public_key).await,
+        }
+    }
+}
+
+impl fmt::Debug for Device {
+    /// Only prints fingerprints and metadata, never the private key or the client id blob,
+    /// so devices can be logged safely.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let certificate = SignedDrmCertificate::decode(self.client_id.token())
+            .ok()
+            .and_then(|certificate| DrmCertificate::decode(certificate.drm_certificate()).ok());
+        let client_info: Vec<(&str, &str)> = self
+            .client_id
+            .client_info
+            .iter()
+            .map(|name_value| (name_value.name(), name_value.value()))
+            .collect();
+        f.debug_struct("Device")
+            .field("signer", &self.signer)
+            .field("client_id", &fingerprint(&self.client_id.encode_to_vec()))
+            .field("token_type", &self.client_id.r#type())
+            .field(
+                "system_id",
+                &certificate.and_then(|certificate| certificate.system_id),
+            )
+            .field("client_info", &client_info)
+            .finish()
+    }
+}
+
+impl fmt::Debug for SignerBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerBackend::Blocking(_) => f.write_str("Blocking"),
+            SignerBackend::Async(_) => f.write_str("Async"),
+        }
+    }
+}
+
+/// Decodes the client id blob, unwrapping client ids that dumping tools saved inside a
+/// signed license request or a bare license request.
+fn decode_client_id(identification_blob: &[u8]) -> error::Result<ClientIdentification> {
+    let client_id = match ClientIdentification::decode(identification_blob) {
+        Ok(client_id) if has_device_certificate(&client_id) => return Ok(client_id),
+        client_id => client_id,
+    };
+    let license_request = SignedMessage::decode(identification_blob)
+        .ok()
+        .and_then(|signed_message| signed_message.msg)
+        .and_then(|msg| LicenseRequest::decode(msg.as_slice()).ok())
+        .filter(|license_request| license_request.client_id.is_some())
+        .or_else(|| LicenseRequest::decode(identification_blob).ok());
+    if let Some(license_request) = license_request {
+        match license_request.client_id {
+            Some(client_id) if has_device_certificate(&client_id) => {
+                info!("Unwrapped the client id from the license request in the client id blob.");
+                return Ok(client_id);
+            }
+            None if license_request.encrypted_client_id.is_some() => {
+                return Err(Error::Input {
+                    message: "Provided client id blob is a license request with an encrypted \
+                              client id, which cannot be unwrapped."
+                        .to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    client_id.map_err(|error| Error::Input {
+        message: format!("Provided client id blob is not a valid ClientIdentification: {error}"),
+    })
+}
+
+/// Checks that the device certificate in the client id decodes and, if `public_key` is
+/// known, that it certifies the key of the signer.
+fn check_device_certificate(
+    client_id: &ClientIdentification,
+    public_key: Option<Vec<u8>>,
+) -> error::Result<()> {
+    if client_id.r#type() != TokenType::DrmDeviceCertificate {
+        return Ok(());
+    }
+    let signed_certificate =
+        SignedDrmCertificate::decode(client_id.token()).map_err(|error| Error::Input {
+            message: format!(
+                "The token of the client id is not a valid SignedDrmCertificate: {error}"
+            ),
+        })?;
+    let certificate =
+        DrmCertificate::decode(signed_certificate.drm_certificate()).map_err(|error| {
+            Error::Input {
+                message: format!(
+                "The device certificate in the client id is not a valid DrmCertificate: {error}"
+            ),
+            }
+        })?;
+    match (public_key, &certificate.public_key) {
+        (Some(public_key), Some(certificate_key)) if &public_key != certificate_key => {
+            Err(Error::Input {
+                message: format!(
+                    "The device private key does not belong to the device certificate of \
+                     system id {} in the client id (public key {}, certificate key {}).",
+                    certificate.system_id(),
+                    fingerprint(&public_key),
+                    fingerprint(certificate_key)
+                ),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn has_device_certificate(client_id: &ClientIdentification) -> bool {
+    SignedDrmCertificate::decode(client_id.token())
+        .is_ok_and(|certificate| certificate.drm_certificate.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{license_protocol::client_identification::NameValue, signer::RsaDeviceSigner};
+    use openssl::{pkey::Private, rsa::Rsa};
+
+    #[test]
+    fn redacted_debug() {
+        let private_key = Rsa::generate(2048).unwrap();
+        let client_id = ClientIdentification {
+            client_info: vec![NameValue {
+                name: Some("model_name".to_string()),
+                value: Some("Test".to_string()),
+            }],
+            vmp_data: Some(vec![0xAB; 32]),
+            ..Default::default()
+        };
+        let device = Device::from_pem(
+            &private_key.private_key_to_pem().unwrap(),
+            client_id.encode_to_vec(),
+        )
+        .unwrap();
+        let debug = format!("{device:?}");
+        assert!(debug.contains("model_name"));
+        assert!(debug.contains(&fingerprint(&client_id.encode_to_vec())));
+        assert!(!debug.contains(&"ab".repeat(32)));
+        assert!(!debug.contains(&hex::encode(private_key.d().to_vec())));
+        let signer = RsaDeviceSigner::new(private_key).unwrap();
+        assert!(format!("{signer:?}").contains("bits: 2048"));
+    }
+
+    #[test]
+    fn invalid_pem() {
+        let client_id = ClientIdentification::default().encode_to_vec();
+        assert!(Device::from_pem(b"not a private key", client_id).is_err());
+        assert!(Device::from_pem(crate::test_private_key(), vec![0xff, 0xff]).is_err());
+    }
+
+    #[test]
+    fn wrapped_client_id() {
+        let client_id = ClientIdentification {
+            r#type: Some(1),
+            token: Some(
+                SignedDrmCertificate {
+                    drm_certificate: Some(
+                        DrmCertificate {
+                            system_id: Some(4464),
+                            ..Default::default()
+                        }
+                        .encode_to_vec(),
+                    ),
+                    signature: Some(vec![0x01; 256]),
+                    ..Default::default()
+                }
+                .encode_to_vec(),
+            ),
+            ..Default::default()
+        };
+        let license_request = LicenseRequest {
+            client_id: Some(client_id.clone()),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let signed_message = SignedMessage {
+            r#type: Some(1),
+            msg: Some(license_request.clone()),
+            signature: Some(vec![0x02; 256]),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        for blob in [client_id.encode_to_vec(), license_request, signed_message] {
+            assert_eq!(decode_client_id(&blob).unwrap(), client_id);
+        }
+        let client_id = ClientIdentification::default();
+        assert_eq!(
+            decode_client_id(&client_id.encode_to_vec()).unwrap(),
+            client_id
+        );
+        assert!(decode_client_id(b"\xff\xff").is_err());
+    }
+
+    #[test]
+    fn device_certificate_mismatch() {
+        let private_key = Rsa::generate(2048).unwrap();
+        let client_id = |token: Vec<u8>| {
+            ClientIdentification {
+                r#type: Some(TokenType::DrmDeviceCertificate.into()),
+                token: Some(token),
+                ..Default::default()
+            }
+            .encode_to_vec()
+        };
+        let certificate = SignedDrmCertificate {
+            drm_certificate: Some(
+                DrmCertificate {
+                    public_key: Some(private_key.public_key_to_der_pkcs1().unwrap()),
+                    ..Default::default()
+                }
+                .encode_to_vec(),
+            ),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        let signer =
+            |private_key: Rsa<Private>| Box::new(RsaDeviceSigner::new(private_key).unwrap());
+
+        assert!(Device::with_signer(signer(private_key), client_id(certificate.clone())).is_ok());
+        let error =
+            Device::with_signer(signer(Rsa::generate(2048).unwrap()), client_id(certificate))
+                .err()
+                .unwrap();
+        assert!(error.to_string().contains("does not belong"));
+        let error =
+            Device::with_signer(signer(Rsa::generate(2048).unwrap()), client_id(vec![0xff]))
+                .err()
+                .unwrap();
+        assert!(error.to_string().contains("SignedDrmCertificate"));
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    license_protocol::{SignedDrmCertificate, SignedMessage},
+    pssh::Pssh,
+};
+use base64::{engine::general_purpose, Engine as _};
+use prost::Message;
+
+/// Decodes a value that is transported either as raw bytes, as hex or as base64 (standard
+/// or url safe, with or without padding). The text forms are tried first and the first
+/// form accepted by `decode` is returned.
+pub fn decode_transport<T>(
+    input: &[u8],
+    decode: impl Fn(&[u8]) -> error::Result<T>,
+) -> error::Result<T> {
+    let text = std::str::from_utf8(input)
+        .map(str::trim)
+        .unwrap_or_default();
+    let hex = Some(text)
+        .filter(|text| !text.is_empty() && text.chars().all(|char| char.is_ascii_hexdigit()))
+        .and_then(|text| hex::decode(text).ok());
+    let base64 = [
+        &general_purpose::STANDARD,
+        &general_purpose::STANDARD_NO_PAD,
+        &general_purpose::URL_SAFE,
+        &general_purpose::URL_SAFE_NO_PAD,
+    ]
+    .into_iter()
+    .find_map(|engine| engine.decode(text).ok())
+    .filter(|bytes| !bytes.is_empty());
+    let mut last_error = None;
+    for candidate in [hex, base64].into_iter().flatten() {
+        match decode(&candidate) {
+            Ok(value) => return Ok(value),
+            Err(error) => last_error = Some(error),
+        }
+    }
+    decode(input).map_err(|error| last_error.unwrap_or(error))
+}
+
+fn decode_signed_message(bytes: &[u8]) -> error::Result<SignedMessage> {
+    SignedMessage::decode(bytes)
+        .ok()
+        .filter(|signed_message| signed_message.msg.is_some())
+        .ok_or_else(|| Error::Input {
+            message: "Provided data is not a valid SignedMessage.".to_string(),
+        })
+}
+
+fn decode_signed_drm_certificate(bytes: &[u8]) -> error::Result<SignedDrmCertificate> {
+    SignedDrmCertificate::decode(bytes)
+        .ok()
+        .filter(|certificate| {
+            certificate.drm_certificate.is_some() && certificate.signature.is_some()
+        })
+        .ok_or_else(|| Error::Input {
+            message: "Provided data is not a valid SignedDrmCertificate.".to_string(),
+        })
+}
+
+macro_rules! transport_conversions {
+    ($type:ty, $decode:expr) => {
+        impl TryFrom<&[u8]> for $type {
+            type Error = Error;
+
+            /// Accepts raw, hex and base64 encoded data.
+            fn try_from(input: &[u8]) -> Result<Self, Self::Error> {
+                decode_transport(input, $decode)
+            }
+        }
+
+        impl TryFrom<&str> for $type {
+            type Error = Error;
+
+            /// Accepts hex and base64 encoded data.
+            fn try_from(input: &str) -> Result<Self, Self::Error> {
+                decode_transport(input.as_bytes(), $decode)
+            }
+        }
+    };
+}
+
+transport_conversions!(Pssh, Pssh::from_bytes);
+transport_conversions!(SignedMessage, decode_signed_message);
+transport_conversions!(SignedDrmCertificate, decode_signed_drm_certificate);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kid::Kid, COMMON_SERVICE_CERTIFICATE};
+
+    #[test]
+    fn transport_forms() {
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+        let bytes = pssh.to_bytes();
+        for form in [
+            hex::encode(&bytes),
+            general_purpose::STANDARD.encode(&bytes),
+            general_purpose::URL_SAFE_NO_PAD.encode(&bytes),
+        ] {
+            assert_eq!(Pssh::try_from(form.as_str()).unwrap(), pssh);
+        }
+        assert_eq!(Pssh::try_from(bytes.as_slice()).unwrap(), pssh);
+        assert!(Pssh::try_from("not a pssh").is_err());
+
+        let signed_message = SignedMessage::try_from(
+            general_purpose::STANDARD
+                .encode(COMMON_SERVICE_CERTIFICATE)
+                .as_str(),
+        )
+        .unwrap();
+        let certificate = SignedDrmCertificate::try_from(signed_message.msg()).unwrap();
+        assert_eq!(
+            SignedDrmCertificate::try_from(hex::encode(signed_message.msg()).as_str()).unwrap(),
+            certificate
+        );
+    }
+}
@@ -0,0 +1,436 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    Device, Session,
+};
+use base64::{engine::general_purpose, Engine as _};
+use rand::random;
+#[cfg(feature = "json")]
+use serde_json::{Map, Value};
+
+/// Wraps a license request in the envelope a license proxy expects.
+pub trait RequestWrapper {
+    fn wrap(&self, challenge: &[u8]) -> error::Result<Vec<u8>>;
+
+    /// `Content-Type` of the wrapped request body.
+    fn content_type(&self) -> &str;
+}
+
+/// The request body is the license request itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RawRequest;
+
+impl RequestWrapper for RawRequest {
+    fn wrap(&self, challenge: &[u8]) -> error::Result<Vec<u8>> {
+        Ok(challenge.to_vec())
+    }
+
+    fn content_type(&self) -> &str {
+        "application/octet-stream"
+    }
+}
+
+/// The license request is sent as a base64 string inside a JSON object, e.g.
+/// `{"rawLicenseRequestBase64": "CAES..."}`.
+#[cfg(feature = "json")]
+#[derive(Clone, Debug)]
+pub struct JsonBase64Request {
+    path: Vec<String>,
+    fields: Map<String, Value>,
+}
+
+#[cfg(feature = "json")]
+impl JsonBase64Request {
+    /// `path` is a dot separated list of object keys, e.g. `payload.challenge`.
+    pub fn new(path: &str) -> JsonBase64Request {
+        JsonBase64Request {
+            path: path.split('.').map(String::from).collect(),
+            fields: Map::new(),
+        }
+    }
+
+    /// Adds a constant top level field to the body, e.g. a content id or an auth token.
+    pub fn with_field(mut self, key: &str, value: Value) -> JsonBase64Request {
+        self.fields.insert(key.to_string(), value);
+        self
+    }
+}
+
+#[cfg(feature = "json")]
+impl RequestWrapper for JsonBase64Request {
+    fn wrap(&self, challenge: &[u8]) -> error::Result<Vec<u8>> {
+        let mut body = Value::Object(self.fields.clone());
+        let mut value = &mut body;
+        for key in &self.path {
+            let Value::Object(object) = value else {
+                return Err(Error::Input {
+                    message: format!("{key} of the request path is not inside an object."),
+                });
+            };
+            value = object
+                .entry(key.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+        }
+        *value = Value::String(general_purpose::STANDARD.encode(challenge));
+        Ok(serde_json::to_vec(&body)?)
+    }
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+}
+
+/// The license request is sent as a field of an `application/x-www-form-urlencoded` body,
+/// e.g. `challenge=CAES...&contentId=movie`.
+#[derive(Clone, Debug)]
+pub struct FormRequest {
+    field: String,
+    fields: Vec<(String, String)>,
+    base64: bool,
+}
+
+impl FormRequest {
+    pub fn new(field: &str) -> FormRequest {
+        FormRequest {
+            field: field.to_string(),
+            fields: Vec::new(),
+            base64: false,
+        }
+    }
+
+    /// Base64 encodes the license request before it is percent-encoded.
+    pub fn base64(mut self, base64: bool) -> FormRequest {
+        self.base64 = base64;
+        self
+    }
+
+    /// Adds a constant field to the body, e.g. a content id or an auth token.
+    pub fn with_field(mut self, key: &str, value: &str) -> FormRequest {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl RequestWrapper for FormRequest {
+    fn wrap(&self, challenge: &[u8]) -> error::Result<Vec<u8>> {
+        let challenge = if self.base64 {
+            general_purpose::STANDARD.encode(challenge).into_bytes()
+        } else {
+            challenge.to_vec()
+        };
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(key, value)| (key.as_bytes(), value.as_bytes()))
+            .chain([(self.field.as_bytes(), challenge.as_slice())])
+            .map(|(key, value)| format!("{}={}", form_encode(key), form_encode(value)))
+            .collect();
+        Ok(fields.join("&").into_bytes())
+    }
+
+    fn content_type(&self) -> &str {
+        "application/x-www-form-urlencoded"
+    }
+}
+
+/// The license request is sent as a part of a `multipart/form-data` body, as a binary file
+/// part or as base64 text.
+#[derive(Clone, Debug)]
+pub struct MultipartRequest {
+    field: String,
+    fields: Vec<(String, String)>,
+    base64: bool,
+    boundary: String,
+    content_type: String,
+}
+
+impl MultipartRequest {
+    pub fn new(field: &str) -> MultipartRequest {
+        let boundary = format!("rust-widevine-{}", hex::encode(random::<[u8; 16]>()));
+        MultipartRequest {
+            field: field.to_string(),
+            fields: Vec::new(),
+            base64: false,
+            content_type: format!("multipart/form-data; boundary={boundary}"),
+            boundary,
+        }
+    }
+
+    /// Sends the license request as a base64 text part instead of a binary file part.
+    pub fn base64(mut self, base64: bool) -> MultipartRequest {
+        self.base64 = base64;
+        self
+    }
+
+    /// Adds a constant text part to the body, e.g. a content id or an auth token.
+    pub fn with_field(mut self, key: &str, value: &str) -> MultipartRequest {
+        self.fields.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl RequestWrapper for MultipartRequest {
+    fn wrap(&self, challenge: &[u8]) -> error::Result<Vec<u8>> {
+        let mut body = Vec::new();
+        for (key, value) in &self.fields {
+            body.extend(
+                format!(
+                    "--{}\r\nContent-Disposition: form-data; name=\"{key}\"\r\n\r\n{value}\r\n",
+                    self.boundary
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend(format!("--{}\r\n", self.boundary).as_bytes());
+        if self.base64 {
+            body.extend(
+                format!(
+                    "Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}",
+                    self.field,
+                    general_purpose::STANDARD.encode(challenge)
+                )
+                .as_bytes(),
+            );
+        } else {
+            body.extend(
+                format!(
+                    "Content-Disposition: form-data; name=\"{0}\"; filename=\"{0}\"\r\n\
+                     Content-Type: application/octet-stream\r\n\r\n",
+                    self.field
+                )
+                .as_bytes(),
+            );
+            body.extend(challenge);
+        }
+        body.extend(format!("\r\n--{}--\r\n", self.boundary).as_bytes());
+        Ok(body)
+    }
+
+    fn content_type(&self) -> &str {
+        &self.content_type
+    }
+}
+
+/// Unwraps the raw Widevine license from the envelope a license proxy responds with.
+pub trait ResponseExtractor {
+    fn extract(&self, response: &[u8]) -> error::Result<Vec<u8>>;
+}
+
+/// The response body is the license itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RawResponse;
+
+impl ResponseExtractor for RawResponse {
+    fn extract(&self, response: &[u8]) -> error::Result<Vec<u8>> {
+        Ok(response.to_vec())
+    }
+}
+
+/// The license is a base64 string inside a JSON object, e.g. `{"license": "CAIS..."}`.
+#[cfg(feature = "json")]
+#[derive(Clone, Debug)]
+pub struct JsonBase64Field {
+    path: Vec<String>,
+}
+
+#[cfg(feature = "json")]
+impl JsonBase64Field {
+    /// `path` is a dot separated list of object keys, e.g. `data.license`.
+    pub fn new(path: &str) -> JsonBase64Field {
+        JsonBase64Field {
+            path: path.split('.').map(String::from).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl ResponseExtractor for JsonBase64Field {
+    fn extract(&self, response: &[u8]) -> error::Result<Vec<u8>> {
+        let value: Value = serde_json::from_slice(response)?;
+        let field = self
+            .path
+            .iter()
+            .try_fold(&value, |value, key| value.get(key))
+            .and_then(Value::as_str)
+            .ok_or_else(|| missing_field(&self.path.join("."), response))?;
+        decode_base64(field, response)
+    }
+}
+
+/// The license is base64 text inside a (possibly nested) XML element,
+/// e.g. `<Envelope><Body><License>CAIS...</License></Body></Envelope>`.
+#[derive(Clone, Debug)]
+pub struct XmlBase64Field {
+    path: Vec<String>,
+}
+
+impl XmlBase64Field {
+    /// `path` is a slash separated list of element names, e.g. `Body/License`.
+    pub fn new(path: &str) -> XmlBase64Field {
+        XmlBase64Field {
+            path: path.split('/').map(String::from).collect(),
+        }
+    }
+}
+
+impl ResponseExtractor for XmlBase64Field {
+    fn extract(&self, response: &[u8]) -> error::Result<Vec<u8>> {
+        let document = String::from_utf8_lossy(response);
+        let field = self
+            .path
+            .iter()
+            .try_fold(document.as_ref(), |document, tag| {
+                xml_element(document, tag)
+            })
+            .ok_or_else(|| missing_field(&self.path.join("/"), response))?;
+        let field = field
+            .trim()
+            .trim_start_matches("<![CDATA[")
+            .trim_end_matches("]]>");
+        decode_base64(field, response)
+    }
+}
+
+impl Session {
+    /// Creates a license request and wraps it with `wrapper`.
+    pub fn create_license_request_with(
+        &mut self,
+        ldm: &Device,
+        pssh: Vec<u8>,
+        wrapper: &dyn RequestWrapper,
+    ) -> error::Result<Vec<u8>> {
+        let challenge = self.create_license_request(ldm, pssh)?;
+        wrapper.wrap(&challenge)
+    }
+
+    /// Unwraps the license response with `extractor` before parsing it.
+    pub fn parse_license_with(
+        &mut self,
+        ldm: &Device,
+        response: &[u8],
+        extractor: &dyn ResponseExtractor,
+    ) -> error::Result<bool> {
+        let license = extractor.extract(response)?;
+        self.parse_license(ldm, license)
+    }
+}
+
+/// Returns the content of the first `tag` element, ignoring namespace prefixes and attributes.
+fn xml_element<'a>(document: &'a str, tag: &str) -> Option<&'a str> {
+    let mut rest = document;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let name = rest[..end].split_whitespace().next().unwrap_or_default();
+        let local_name = name.rsplit(':').next().unwrap_or_default();
+        if local_name != tag || rest[..end].ends_with('/') {
+            continue;
+        }
+        let content = &rest[end + 1..];
+        let closing = format!("</{name}>");
+        return content.find(&closing).map(|close| &content[..close]);
+    }
+    None
+}
+
+/// Percent-encodes `value` like `application/x-www-form-urlencoded` serializers do.
+pub(crate) fn form_encode(value: &[u8]) -> String {
+    let mut encoded = String::new();
+    for &byte in value {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                encoded.push(byte as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn decode_base64(field: &str, response: &[u8]) -> error::Result<Vec<u8>> {
+    general_purpose::STANDARD
+        .decode(field.trim())
+        .map_err(|error| Error::Decode {
+            message: format!("License field is not valid base64: {error}"),
+            content: response.to_vec(),
+            url: "n/a".to_string(),
+        })
+}
+
+fn missing_field(path: &str, response: &[u8]) -> Error {
+    Error::Decode {
+        message: format!("License response does not contain {path}"),
+        content: response.to_vec(),
+        url: "n/a".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_envelopes() {
+        let wrapper = JsonBase64Request::new("payload.challenge")
+            .with_field("contentId", Value::String("movie".to_string()));
+        let body: Value = serde_json::from_slice(&wrapper.wrap(&[1, 2, 3]).unwrap()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({"contentId": "movie", "payload": {"challenge": "AQID"}})
+        );
+        let wrapper = JsonBase64Request::new("contentId.challenge")
+            .with_field("contentId", Value::String("movie".to_string()));
+        assert!(wrapper.wrap(&[1, 2, 3]).is_err());
+
+        let json = br#"{"status": "OK", "data": {"license": "AQID"}}"#;
+        let extractor = JsonBase64Field::new("data.license");
+        assert_eq!(extractor.extract(json).unwrap(), vec![1, 2, 3]);
+        assert!(JsonBase64Field::new("license").extract(json).is_err());
+    }
+
+    #[test]
+    fn request_wrappers() {
+        assert_eq!(RawRequest.wrap(&[1, 2, 3]).unwrap(), vec![1, 2, 3]);
+
+        let wrapper = FormRequest::new("challenge").with_field("content id", "a&b");
+        assert_eq!(
+            wrapper.wrap(&[0xff, b'a', b' ']).unwrap(),
+            b"content+id=a%26b&challenge=%FFa+"
+        );
+        let wrapper = FormRequest::new("challenge").base64(true);
+        assert_eq!(
+            wrapper.wrap(&[0xfb, 0xff]).unwrap(),
+            b"challenge=%2B%2F8%3D"
+        );
+
+        let wrapper = MultipartRequest::new("challenge").with_field("contentId", "movie");
+        let boundary = wrapper.content_type().split("boundary=").nth(1).unwrap();
+        let body = String::from_utf8(wrapper.wrap(b"CAES").unwrap()).unwrap();
+        assert_eq!(
+            body,
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"contentId\"\r\n\r\nmovie\r\n\
+                 --{boundary}\r\nContent-Disposition: form-data; name=\"challenge\"; \
+                 filename=\"challenge\"\r\nContent-Type: application/octet-stream\r\n\r\n\
+                 CAES\r\n--{boundary}--\r\n"
+            )
+        );
+        let wrapper = MultipartRequest::new("challenge").base64(true);
+        let body = String::from_utf8(wrapper.wrap(&[1, 2, 3]).unwrap()).unwrap();
+        assert!(body.contains("name=\"challenge\"\r\n\r\nAQID\r\n"));
+    }
+
+    #[test]
+    fn extractors() {
+        assert_eq!(RawResponse.extract(&[1, 2, 3]).unwrap(), vec![1, 2, 3]);
+
+        let xml = br#"<s:Envelope xmlns:s="x"><s:Body><License id="1"> AQID </License></s:Body></s:Envelope>"#;
+        let extractor = XmlBase64Field::new("Body/License");
+        assert_eq!(extractor.extract(xml).unwrap(), vec![1, 2, 3]);
+        assert!(XmlBase64Field::new("Body/Missing").extract(xml).is_err());
+    }
+}
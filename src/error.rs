@@ -1,8 +1,11 @@
 // Copyright 2022-NOW Crunchy Labs Team
 // SPDX-License-Identifier: MIT
 
+#[cfg(feature = "http")]
 use http::StatusCode;
+#[cfg(feature = "http")]
 use serde::Deserialize;
+#[cfg(feature = "http")]
 use serde_json::Value;
 use std::fmt::{Debug, Display, Formatter};
 
@@ -20,6 +23,8 @@ pub enum Error {
     Input {
         message: String,
     },
+    /// An HTTP request failed, only with the `http` feature.
+    #[cfg(feature = "http")]
     Request {
         message: String,
         status: Option<StatusCode>,
@@ -30,17 +35,74 @@ pub enum Error {
         content: Vec<u8>,
         url: String,
     },
+    /// An HTTP request was blocked by bot protection, only with the `http` feature.
+    #[cfg(feature = "http")]
     Block {
         message: String,
         body: String,
         url: String,
     },
+    /// An operation was called in a session state that does not allow it.
+    State {
+        message: String,
+    },
+    /// A session id is not known to the CDM or the session could not be opened.
+    Session {
+        message: String,
+    },
+    /// A service certificate was rejected.
+    Certificate {
+        message: String,
+    },
+    /// The service certificate expired at `expiration_time` (seconds since the unix epoch).
+    CertificateExpired {
+        expiration_time: u64,
+    },
+    /// The CDM already has the maximum number of sessions open.
+    TooManySessions {
+        limit: usize,
+    },
+    /// The license server answered with a protocol level `ERROR_RESPONSE`.
+    License {
+        status: LicenseStatus,
+    },
+    /// Requests to `endpoint` are refused until `open_until` after repeated failures.
+    CircuitOpen {
+        endpoint: String,
+        open_until: u64,
+    },
+}
+
+/// Error codes a license server reports through a `LicenseError` message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LicenseStatus {
+    /// The device credentials are invalid, the device must re-provision.
+    InvalidDeviceCertificate,
+    /// The device credentials have been revoked.
+    DeviceRevoked,
+    /// The license service is currently unavailable.
+    ServiceUnavailable,
+    /// The error response did not contain a known error code.
+    Unknown(Option<i32>),
+}
+
+impl Display for LicenseStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LicenseStatus::InvalidDeviceCertificate => write!(f, "invalid device certificate"),
+            LicenseStatus::DeviceRevoked => write!(f, "device certificate revoked"),
+            LicenseStatus::ServiceUnavailable => write!(f, "service unavailable"),
+            LicenseStatus::Unknown(Some(code)) => write!(f, "unknown error code {code}"),
+            LicenseStatus::Unknown(None) => write!(f, "no error code"),
+        }
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::Internal { message } => write!(f, "{message}"),
+            #[cfg(feature = "http")]
             Error::Request { message, url, .. } => {
                 // the url can be 'n/a' when the error got triggered by the [`From<reqwest::Error>`]
                 // implementation for this error struct
@@ -68,14 +130,35 @@ impl Display for Error {
                 }
             }
             Error::Input { message } => write!(f, "{message}"),
+            Error::State { message } => write!(f, "{message}"),
+            Error::Session { message } => write!(f, "{message}"),
+            Error::Certificate { message } => write!(f, "{message}"),
+            Error::CertificateExpired { expiration_time } => {
+                write!(f, "The service certificate expired at {expiration_time}")
+            }
+            Error::TooManySessions { limit } => {
+                write!(f, "Too many open sessions, the limit is {limit}")
+            }
+            #[cfg(feature = "http")]
             Error::Block { message, body, url } => write!(f, "{message} ({url}): {body}"),
             Error::OpenSSL { message, stack } => write!(f, "{message} {stack}"),
+            Error::License { status } => {
+                write!(f, "The license server responded with an error: {status}")
+            }
+            Error::CircuitOpen {
+                endpoint,
+                open_until,
+            } => write!(
+                f,
+                "Requests to {endpoint} are paused until {open_until} after repeated failures"
+            ),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+#[cfg(feature = "json")]
 impl From<serde_json::Error> for Error {
     fn from(err: serde_json::Error) -> Self {
         Self::Decode {
@@ -86,6 +169,7 @@ impl From<serde_json::Error> for Error {
     }
 }
 
+#[cfg(feature = "client")]
 impl From<reqwest::Error> for Error {
     fn from(err: reqwest::Error) -> Self {
         if err.is_request()
@@ -118,6 +202,7 @@ impl From<reqwest::Error> for Error {
     }
 }
 
+#[cfg(feature = "http")]
 #[allow(dead_code)]
 pub(crate) fn is_request_error(value: Value, url: &String, status: &StatusCode) -> Result<()> {
     #[derive(Debug, Deserialize)]
@@ -202,12 +287,12 @@ pub(crate) fn is_request_error(value: Value, url: &String, status: &StatusCode)
     Ok(())
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "client"))]
 use reqwest::Response;
-#[cfg(test)]
+#[cfg(all(test, feature = "client"))]
 use serde::de::DeserializeOwned;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "client"))]
 pub(crate) async fn check_request<T: DeserializeOwned>(url: String, resp: Response) -> Result<T> {
     let content_length = resp.content_length().unwrap_or(0);
     let status = resp.status();
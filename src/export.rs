@@ -0,0 +1,174 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    license_protocol::license::key_container::KeyType,
+    Device, KeyContainer, Session, SessionState,
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+
+/// The session state that has to survive between sending a challenge with an
+/// external tool (curl, browser devtools, ...) and parsing its license response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionContext {
+    /// Base64 encoded session id.
+    pub session_id: String,
+    /// Base64 encoded unsigned license request, required to derive the session keys.
+    pub raw_license_request: String,
+}
+
+/// A challenge together with the [`SessionContext`] needed to import its response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedChallenge {
+    /// Base64 encoded signed license request, to be posted to the license server as raw bytes.
+    pub challenge: String,
+    pub context: SessionContext,
+}
+
+impl ExportedChallenge {
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> error::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> error::Result<ExportedChallenge> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn challenge_bytes(&self) -> error::Result<Vec<u8>> {
+        decode_base64("challenge", &self.challenge)
+    }
+}
+
+/// A single key of a [`ClearKeyLicense`], as JSON Web Key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClearKey {
+    pub kty: String,
+    /// Base64url encoded key id without padding.
+    pub kid: String,
+    /// Base64url encoded key without padding.
+    pub k: String,
+}
+
+/// An EME ClearKey license (JWK set) for sanity testing decrypted playback in test players.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClearKeyLicense {
+    pub keys: Vec<ClearKey>,
+    #[serde(rename = "type")]
+    pub license_type: String,
+}
+
+impl ClearKeyLicense {
+    /// Converts the content keys, other keys (e.g. signing keys) are skipped.
+    pub fn from_keys(keys: &[KeyContainer]) -> ClearKeyLicense {
+        let keys = keys
+            .iter()
+            .filter(|key| key.key_type == KeyType::Content)
+            .filter_map(|key| {
+                let k = hex::decode(&key.key).ok()?;
+                Some(ClearKey {
+                    kty: "oct".to_string(),
+                    kid: general_purpose::URL_SAFE_NO_PAD.encode(key.kid),
+                    k: general_purpose::URL_SAFE_NO_PAD.encode(k),
+                })
+            })
+            .collect();
+        ClearKeyLicense {
+            keys,
+            license_type: "temporary".to_string(),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> error::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl Session {
+    /// Creates a license request and exports it together with the session context.
+    pub fn export_challenge(
+        &mut self,
+        ldm: &Device,
+        pssh: Vec<u8>,
+    ) -> error::Result<ExportedChallenge> {
+        let challenge = self.create_license_request(ldm, pssh)?;
+        Ok(ExportedChallenge {
+            challenge: general_purpose::STANDARD.encode(challenge),
+            context: self.export_context()?,
+        })
+    }
+
+    pub fn export_context(&self) -> error::Result<SessionContext> {
+        let raw_license_request = self.raw_license_request.as_ref().ok_or(Error::Input {
+            message: "No license request has been created in this session.".to_string(),
+        })?;
+        Ok(SessionContext {
+            session_id: general_purpose::STANDARD.encode(&self.session_id),
+            raw_license_request: general_purpose::STANDARD.encode(raw_license_request),
+        })
+    }
+
+    /// Restores a session from an exported context so that the license response
+    /// can be imported with [`Session::parse_license`].
+    pub fn from_context(context: &SessionContext) -> error::Result<Session> {
+        let mut session = Session::new();
+        session.session_id = decode_base64("session_id", &context.session_id)?;
+        session.raw_license_request = Some(decode_base64(
+            "raw_license_request",
+            &context.raw_license_request,
+        )?);
+        session.state = SessionState::ChallengeSent;
+        Ok(session)
+    }
+}
+
+fn decode_base64(field: &str, value: &str) -> error::Result<Vec<u8>> {
+    general_purpose::STANDARD
+        .decode(value.trim())
+        .map_err(|error| Error::Input {
+            message: format!("Field {field} is not valid base64: {error}"),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_round_trip() {
+        let mut session = Session::new();
+        assert!(session.export_context().is_err());
+        session.raw_license_request = Some(vec![1, 2, 3]);
+        let exported = ExportedChallenge {
+            challenge: general_purpose::STANDARD.encode([4, 5, 6]),
+            context: session.export_context().unwrap(),
+        };
+        let json = serde_json::to_string(&exported).unwrap();
+        let imported: ExportedChallenge = serde_json::from_str(&json).unwrap();
+        assert_eq!(imported, exported);
+        assert_eq!(imported.challenge_bytes().unwrap(), vec![4, 5, 6]);
+        let restored = Session::from_context(&imported.context).unwrap();
+        assert_eq!(restored.session_id, session.session_id);
+        assert_eq!(restored.raw_license_request, Some(vec![1, 2, 3]));
+        assert_eq!(restored.state(), SessionState::ChallengeSent);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn clear_key_license() {
+        let keys = vec![
+            KeyContainer::new(crate::kid::Kid::default(), &"00".repeat(64))
+                .with_key_type(KeyType::Signing),
+            KeyContainer::new([0xFF; 16], &"00".repeat(16)),
+        ];
+        let license = ClearKeyLicense::from_keys(&keys);
+        assert_eq!(
+            license.to_json().unwrap(),
+            r#"{"keys":[{"kty":"oct","kid":"_____________________w","k":"AAAAAAAAAAAAAAAAAAAAAA"}],"type":"temporary"}"#
+        );
+    }
+}
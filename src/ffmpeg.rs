@@ -0,0 +1,87 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    KeyContainer,
+};
+use std::{fs, path::Path};
+
+/// Arguments that pass `key` to ffmpeg's mp4 demuxer, `-decryption_key <hex key>`.
+pub fn decryption_key_args(key: &KeyContainer) -> [String; 2] {
+    ["-decryption_key".to_string(), key.key.clone()]
+}
+
+/// Contents of an ffmpeg key info file (`-hls_key_info_file`): the key URI written to the
+/// playlist, the path of the key file and optionally the IV.
+pub fn key_info(key_uri: &str, key_path: &Path, iv: Option<&[u8; 16]>) -> String {
+    let mut key_info = format!("{key_uri}\n{}\n", key_path.display());
+    if let Some(iv) = iv {
+        key_info.push_str(&hex::encode(iv));
+        key_info.push('\n');
+    }
+    key_info
+}
+
+/// Writes the raw bytes of `key` to `key_path` and a key info file referencing it to
+/// `key_info_path`.
+pub fn write_key_info(
+    key: &KeyContainer,
+    key_uri: &str,
+    key_path: &Path,
+    key_info_path: &Path,
+    iv: Option<&[u8; 16]>,
+) -> error::Result<()> {
+    let key_bytes = hex::decode(&key.key).map_err(|error| Error::Input {
+        message: format!("Key {} is not valid hex: {error}", key.kid),
+    })?;
+    write(key_path, &key_bytes)?;
+    write(key_info_path, key_info(key_uri, key_path, iv).as_bytes())
+}
+
+/// Runs `ffmpeg` to decrypt `input` with `key` and remux it into `output` without
+/// re-encoding.
+#[cfg(feature = "ffmpeg")]
+pub fn remux(input: &Path, output: &Path, key: &KeyContainer) -> error::Result<()> {
+    let status = std::process::Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(decryption_key_args(key))
+        .arg("-i")
+        .arg(input)
+        .args(["-map", "0", "-c", "copy"])
+        .arg(output)
+        .status()
+        .map_err(|error| Error::Internal {
+            message: format!("Failed to run ffmpeg: {error}"),
+        })?;
+    if !status.success() {
+        return Err(Error::Internal {
+            message: format!("ffmpeg failed to remux {}: {status}", input.display()),
+        });
+    }
+    Ok(())
+}
+
+fn write(path: &Path, contents: &[u8]) -> error::Result<()> {
+    fs::write(path, contents).map_err(|error| Error::Input {
+        message: format!("Failed to write {}: {error}", path.display()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_info_file() {
+        let key = KeyContainer::new([0x11; 16], &"17".repeat(16));
+        assert_eq!(
+            decryption_key_args(&key),
+            ["-decryption_key".to_string(), "17".repeat(16)]
+        );
+        assert_eq!(
+            key_info("key.bin", Path::new("/tmp/key.bin"), Some(&[0x01; 16])),
+            format!("key.bin\n/tmp/key.bin\n{}\n", "01".repeat(16))
+        );
+    }
+}
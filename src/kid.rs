@@ -0,0 +1,180 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::error::Error;
+use base64::{engine::general_purpose, Engine as _};
+use std::{fmt, str::FromStr};
+
+/// A 16 byte content key id.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Kid(pub [u8; 16]);
+
+impl Kid {
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+
+    /// Formats the key id as UUID, e.g. `eb676abb-cb34-5e96-bbcf-616630f1a3da`.
+    pub fn to_uuid(&self) -> String {
+        let hex = self.to_string();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.0)
+    }
+}
+
+impl fmt::Display for Kid {
+    /// Lowercase hex without separators.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+impl FromStr for Kid {
+    type Err = Error;
+
+    /// Accepts hex, UUID (with or without braces) and base64 encoded key ids.
+    fn from_str(kid: &str) -> Result<Self, Self::Err> {
+        let kid = kid.trim();
+        let uuid = kid
+            .trim_start_matches('{')
+            .trim_end_matches('}')
+            .replace('-', "");
+        let bytes = if uuid.len() == 32 && uuid.chars().all(|char| char.is_ascii_hexdigit()) {
+            hex::decode(uuid).ok()
+        } else {
+            general_purpose::STANDARD
+                .decode(kid)
+                .or_else(|_| general_purpose::STANDARD_NO_PAD.decode(kid))
+                .or_else(|_| general_purpose::URL_SAFE_NO_PAD.decode(kid))
+                .ok()
+        };
+        bytes
+            .as_deref()
+            .and_then(|bytes| Kid::try_from(bytes).ok())
+            .ok_or_else(|| Error::Input {
+                message: format!("{kid} is not a valid key id."),
+            })
+    }
+}
+
+impl From<[u8; 16]> for Kid {
+    fn from(kid: [u8; 16]) -> Self {
+        Kid(kid)
+    }
+}
+
+impl From<&[u8; 16]> for Kid {
+    fn from(kid: &[u8; 16]) -> Self {
+        Kid(*kid)
+    }
+}
+
+impl From<Kid> for [u8; 16] {
+    fn from(kid: Kid) -> Self {
+        kid.0
+    }
+}
+
+impl From<Kid> for Vec<u8> {
+    fn from(kid: Kid) -> Self {
+        kid.0.to_vec()
+    }
+}
+
+/// The numeric value of the key id as UUID, big endian like the `uuid` crate.
+impl From<u128> for Kid {
+    fn from(kid: u128) -> Self {
+        Kid(kid.to_be_bytes())
+    }
+}
+
+impl From<Kid> for u128 {
+    fn from(kid: Kid) -> Self {
+        u128::from_be_bytes(kid.0)
+    }
+}
+
+impl TryFrom<&str> for Kid {
+    type Error = Error;
+
+    fn try_from(kid: &str) -> Result<Self, Self::Error> {
+        kid.parse()
+    }
+}
+
+impl TryFrom<String> for Kid {
+    type Error = Error;
+
+    fn try_from(kid: String) -> Result<Self, Self::Error> {
+        kid.parse()
+    }
+}
+
+impl TryFrom<&[u8]> for Kid {
+    type Error = Error;
+
+    fn try_from(kid: &[u8]) -> Result<Self, Self::Error> {
+        kid.try_into().map(Kid).map_err(|_| Error::Input {
+            message: format!("A key id has to be 16 bytes long, got {}.", kid.len()),
+        })
+    }
+}
+
+impl TryFrom<Vec<u8>> for Kid {
+    type Error = Error;
+
+    fn try_from(kid: Vec<u8>) -> Result<Self, Self::Error> {
+        Kid::try_from(kid.as_slice())
+    }
+}
+
+impl AsRef<[u8]> for Kid {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_formats() {
+        let kid = Kid([
+            0xEB, 0x67, 0x6A, 0xBB, 0xCB, 0x34, 0x5E, 0x96, 0xBB, 0xCF, 0x61, 0x66, 0x30, 0xF1,
+            0xA3, 0xDA,
+        ]);
+        assert_eq!(kid.to_string(), "eb676abbcb345e96bbcf616630f1a3da");
+        assert_eq!(kid.to_uuid(), "eb676abb-cb34-5e96-bbcf-616630f1a3da");
+        for format in [
+            "EB676ABBCB345E96BBCF616630F1A3DA".to_string(),
+            format!("{{{}}}", kid.to_uuid()),
+            kid.to_base64(),
+        ] {
+            assert_eq!(format.parse::<Kid>().unwrap(), kid);
+        }
+        assert!("eb676abb".parse::<Kid>().is_err());
+    }
+
+    #[test]
+    fn conversions() {
+        let kid = Kid([0x11; 16]);
+        assert_eq!(Kid::try_from(kid.to_uuid().as_str()).unwrap(), kid);
+        assert_eq!(Kid::try_from(kid.to_base64()).unwrap(), kid);
+        assert_eq!(Kid::try_from(Vec::from(kid)).unwrap(), kid);
+        assert!(Kid::try_from(vec![0x11; 15]).is_err());
+        assert_eq!(Kid::from(u128::from(kid)), kid);
+        assert_eq!(Kid::from(0x11u128).as_bytes()[15], 0x11);
+        assert_eq!(Kid::from(&[0x11; 16]), kid);
+    }
+}
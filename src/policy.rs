@@ -0,0 +1,256 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    clock::{Clock, SystemClock},
+    license_protocol::{
+        client_identification::client_capabilities::HdcpVersion, license::Policy, License,
+    },
+    Device,
+};
+use log::warn;
+use std::sync::Arc;
+
+/// Answers playback, renewal and persistence questions for the policy of a parsed license.
+///
+/// All times are seconds since the unix epoch, durations of `0` are unlimited.
+pub struct PolicyEnforcer {
+    policy: Policy,
+    license_start_time: i64,
+    playback_start_time: Option<i64>,
+    clock: Arc<dyn Clock>,
+}
+
+impl PolicyEnforcer {
+    pub fn new(license: &License) -> PolicyEnforcer {
+        PolicyEnforcer::with_clock(license, Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(license: &License, clock: Arc<dyn Clock>) -> PolicyEnforcer {
+        let license_start_time = license
+            .license_start_time
+            .unwrap_or_else(|| clock.now() as i64);
+        PolicyEnforcer {
+            policy: license.policy.clone().unwrap_or_default(),
+            license_start_time,
+            playback_start_time: None,
+            clock,
+        }
+    }
+
+    pub fn can_play(&self) -> bool {
+        self.policy.can_play()
+            && self
+                .playback_expiry()
+                .is_none_or(|expiry| self.now() < expiry)
+    }
+
+    /// Records the start of playback, which starts the playback duration.
+    /// Returns `false` if playback is not allowed.
+    pub fn start_playback(&mut self) -> bool {
+        if !self.can_play() {
+            return false;
+        }
+        if self.playback_start_time.is_none() {
+            self.playback_start_time = Some(self.now());
+        }
+        true
+    }
+
+    /// The time at which playback has to stop, `None` if it is unlimited.
+    pub fn playback_expiry(&self) -> Option<i64> {
+        let after = |start: i64, duration: i64| (duration > 0).then_some(start + duration);
+        let license_end = after(
+            self.license_start_time,
+            self.policy.license_duration_seconds(),
+        );
+        let rental_end = after(
+            self.license_start_time,
+            self.policy.rental_duration_seconds(),
+        );
+        let (rental_end, playback_end) = match self.playback_start_time {
+            None => (rental_end, None),
+            Some(playback_start_time) => (
+                // A soft enforced rental duration only limits the start of playback.
+                rental_end.filter(|_| !self.policy.soft_enforce_rental_duration()),
+                after(playback_start_time, self.policy.playback_duration_seconds()),
+            ),
+        };
+        [license_end, rental_end, playback_end]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// The time by which the license has to be renewed, `None` if it can not be renewed
+    /// or does not need to be.
+    pub fn renew_by(&self) -> Option<i64> {
+        if !self.policy.can_renew() || self.policy.renewal_delay_seconds() <= 0 {
+            return None;
+        }
+        Some(self.license_start_time + self.policy.renewal_delay_seconds())
+    }
+
+    pub fn must_renew(&self) -> bool {
+        self.renew_by()
+            .is_some_and(|renew_by| self.now() >= renew_by)
+    }
+
+    /// The license may be stored for offline playback.
+    pub fn can_persist(&self) -> bool {
+        self.policy.can_persist()
+    }
+
+    fn now(&self) -> i64 {
+        self.clock.now() as i64
+    }
+}
+
+/// Device requirements a service is known to enforce, checked before a license request is
+/// sent to a server that would refuse it anyway.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeviceRequirements {
+    /// Worst accepted Widevine security level, e.g. `1` for L1-only services.
+    pub security_level: Option<u8>,
+    pub min_hdcp_version: Option<HdcpVersion>,
+    pub min_oem_crypto_api_version: Option<u32>,
+}
+
+/// A requirement the device does not meet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RequirementMismatch {
+    /// `device` is `None` if the security level of the device is unknown.
+    SecurityLevel {
+        required: u8,
+        device: Option<u8>,
+    },
+    Hdcp {
+        required: HdcpVersion,
+        device: HdcpVersion,
+    },
+    OemCryptoApiVersion {
+        required: u32,
+        device: Option<u32>,
+    },
+}
+
+impl DeviceRequirements {
+    /// Requirements of services that only hand out keys to L1 devices.
+    pub fn l1_only() -> DeviceRequirements {
+        DeviceRequirements {
+            security_level: Some(1),
+            ..Default::default()
+        }
+    }
+
+    /// Compares the capabilities `device` declares in its client id and its `security_level`
+    /// (e.g. from its `.wvd` file) with the requirements and warns about every mismatch.
+    pub fn check(&self, device: &Device, security_level: Option<u8>) -> Vec<RequirementMismatch> {
+        let capabilities = device
+            .client_id()
+            .client_capabilities
+            .clone()
+            .unwrap_or_default();
+        let mut mismatches = Vec::new();
+        if let Some(required) = self.security_level {
+            if security_level.is_none_or(|security_level| security_level > required) {
+                mismatches.push(RequirementMismatch::SecurityLevel {
+                    required,
+                    device: security_level,
+                });
+            }
+        }
+        if let Some(required) = self.min_hdcp_version {
+            let device = capabilities.max_hdcp_version();
+            if (device as i32) < (required as i32) {
+                mismatches.push(RequirementMismatch::Hdcp { required, device });
+            }
+        }
+        if let Some(required) = self.min_oem_crypto_api_version {
+            let device = capabilities.oem_crypto_api_version;
+            if device.is_none_or(|device| device < required) {
+                mismatches.push(RequirementMismatch::OemCryptoApiVersion { required, device });
+            }
+        }
+        for mismatch in &mismatches {
+            warn!("Device does not meet a service requirement: {mismatch:?}");
+        }
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn playback_window() {
+        let license = License {
+            license_start_time: Some(1000),
+            policy: Some(Policy {
+                can_play: Some(true),
+                can_renew: Some(true),
+                license_duration_seconds: Some(100),
+                rental_duration_seconds: Some(30),
+                playback_duration_seconds: Some(50),
+                renewal_delay_seconds: Some(60),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let clock = Arc::new(ManualClock::new(1010));
+        let mut enforcer = PolicyEnforcer::with_clock(&license, clock.clone());
+        assert_eq!(enforcer.playback_expiry(), Some(1030));
+        assert!(enforcer.start_playback());
+        assert_eq!(enforcer.playback_expiry(), Some(1060));
+        clock.set(1040);
+        assert!(enforcer.can_play());
+        assert!(!enforcer.must_renew());
+        clock.advance(20);
+        assert!(!enforcer.can_play());
+        assert!(enforcer.must_renew());
+        assert!(!enforcer.can_persist());
+    }
+
+    #[test]
+    fn device_requirements() {
+        use crate::license_protocol::{
+            client_identification::ClientCapabilities, ClientIdentification,
+        };
+        use prost::Message;
+
+        let client_id = ClientIdentification {
+            client_capabilities: Some(ClientCapabilities {
+                max_hdcp_version: Some(HdcpVersion::HdcpV1.into()),
+                oem_crypto_api_version: Some(16),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let device =
+            Device::from_pem(crate::test_private_key(), client_id.encode_to_vec()).unwrap();
+        assert!(DeviceRequirements::default()
+            .check(&device, None)
+            .is_empty());
+        assert_eq!(
+            DeviceRequirements::l1_only().check(&device, Some(3)),
+            [RequirementMismatch::SecurityLevel {
+                required: 1,
+                device: Some(3)
+            }]
+        );
+        let requirements = DeviceRequirements {
+            min_hdcp_version: Some(HdcpVersion::HdcpV22),
+            min_oem_crypto_api_version: Some(16),
+            ..Default::default()
+        };
+        assert_eq!(
+            requirements.check(&device, Some(1)),
+            [RequirementMismatch::Hdcp {
+                required: HdcpVersion::HdcpV22,
+                device: HdcpVersion::HdcpV1
+            }]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    kid::Kid,
+    license_protocol::WidevinePsshData,
+    WIDEVINE_SYSTEM_ID,
+};
+use base64::{engine::general_purpose, Engine as _};
+use prost::Message;
+
+const PSSH_BOX_TYPE: &[u8; 4] = b"pssh";
+
+pub const PLAYREADY_SYSTEM_ID: [u8; 16] = [
+    0x9A, 0x04, 0xF0, 0x79, 0x98, 0x40, 0x42, 0x86, 0xAB, 0x92, 0xE6, 0x5B, 0xE0, 0x88, 0x5F, 0x95,
+];
+
+/// PlayReady Object record containing the WRM header.
+const PLAYREADY_RIGHTS_MANAGEMENT_HEADER: u16 = 1;
+
+/// A `pssh` box as defined by ISO/IEC 23001-7 (version 0 and 1).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pssh {
+    pub version: u8,
+    pub flags: u32,
+    pub system_id: [u8; 16],
+    /// Only serialized for version 1 boxes.
+    pub key_ids: Vec<Kid>,
+    pub data: Vec<u8>,
+}
+
+impl Pssh {
+    /// Builds a Widevine PSSH box from key IDs and/or a content ID.
+    ///
+    /// Version 1 boxes additionally carry the key IDs in the box header.
+    pub fn new_widevine(key_ids: Vec<Kid>, content_id: Option<Vec<u8>>, version: u8) -> Pssh {
+        let pssh_data = WidevinePsshData {
+            key_ids: key_ids.iter().map(|key_id| key_id.0.to_vec()).collect(),
+            content_id,
+            ..Default::default()
+        };
+        Pssh {
+            version,
+            flags: 0,
+            system_id: WIDEVINE_SYSTEM_ID,
+            key_ids: if version > 0 { key_ids } else { Vec::new() },
+            data: pssh_data.encode_to_vec(),
+        }
+    }
+
+    pub fn from_bytes(pssh: &[u8]) -> error::Result<Pssh> {
+        let invalid = |message: &str| Error::Input {
+            message: format!("Provided data is not a valid pssh box: {message}."),
+        };
+        if pssh.len() < 32 || &pssh[4..8] != PSSH_BOX_TYPE {
+            return Err(invalid("missing box header"));
+        }
+        let size = u32::from_be_bytes(pssh[0..4].try_into().unwrap()) as usize;
+        if size != pssh.len() {
+            return Err(invalid("box size does not match data length"));
+        }
+        let version = pssh[8];
+        if version > 1 {
+            return Err(invalid("unsupported box version"));
+        }
+        let flags = u32::from_be_bytes([0, pssh[9], pssh[10], pssh[11]]);
+        let system_id: [u8; 16] = pssh[12..28].try_into().unwrap();
+
+        let mut offset = 28;
+        let mut key_ids = Vec::new();
+        if version == 1 {
+            let count = read_u32(pssh, offset).ok_or_else(|| invalid("truncated key id count"))?;
+            offset += 4;
+            for _ in 0..count {
+                let key_id = pssh
+                    .get(offset..offset + 16)
+                    .ok_or_else(|| invalid("truncated key id"))?;
+                key_ids.push(Kid::try_from(key_id)?);
+                offset += 16;
+            }
+        }
+        let data_size =
+            read_u32(pssh, offset).ok_or_else(|| invalid("truncated data size"))? as usize;
+        offset += 4;
+        let data = pssh
+            .get(offset..offset + data_size)
+            .ok_or_else(|| invalid("truncated data"))?
+            .to_vec();
+
+        Ok(Pssh {
+            version,
+            flags,
+            system_id,
+            key_ids,
+            data,
+        })
+    }
+
+    pub fn from_base64(pssh: &str) -> error::Result<Pssh> {
+        let pssh = general_purpose::STANDARD
+            .decode(pssh.trim())
+            .map_err(|error| Error::Input {
+                message: format!("Provided pssh is not valid base64: {error}"),
+            })?;
+        Pssh::from_bytes(&pssh)
+    }
+
+    pub fn is_widevine(&self) -> bool {
+        self.system_id == WIDEVINE_SYSTEM_ID
+    }
+
+    pub fn is_playready(&self) -> bool {
+        self.system_id == PLAYREADY_SYSTEM_ID
+    }
+
+    /// Picks the init data for a Widevine challenge out of the PSSH boxes of a multi-DRM manifest.
+    ///
+    /// If there is no Widevine box, a key ID based one is built from the KIDs of the PlayReady box.
+    pub fn select_widevine(boxes: &[Pssh]) -> error::Result<Pssh> {
+        if let Some(pssh) = boxes.iter().find(|pssh| pssh.is_widevine()) {
+            return Ok(pssh.clone());
+        }
+        let pssh = boxes
+            .iter()
+            .find(|pssh| pssh.is_playready())
+            .ok_or_else(|| Error::Input {
+                message: "Neither a Widevine nor a PlayReady pssh box was provided.".to_string(),
+            })?;
+        let key_ids = playready_key_ids(&pssh.data)?;
+        Ok(Pssh::new_widevine(key_ids, None, 0))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.push(self.version);
+        body.extend_from_slice(&self.flags.to_be_bytes()[1..]);
+        body.extend_from_slice(&self.system_id);
+        if self.version > 0 {
+            body.extend_from_slice(&(self.key_ids.len() as u32).to_be_bytes());
+            for key_id in &self.key_ids {
+                body.extend_from_slice(key_id.as_bytes());
+            }
+        }
+        body.extend_from_slice(&(self.data.len() as u32).to_be_bytes());
+        body.extend_from_slice(&self.data);
+
+        let mut pssh = Vec::with_capacity(body.len() + 8);
+        pssh.extend_from_slice(&((body.len() + 8) as u32).to_be_bytes());
+        pssh.extend_from_slice(PSSH_BOX_TYPE);
+        pssh.extend_from_slice(&body);
+        pssh
+    }
+
+    pub fn to_base64(&self) -> String {
+        general_purpose::STANDARD.encode(self.to_bytes())
+    }
+}
+
+/// Extracts the key IDs from a PlayReady Object, e.g. the data of a PlayReady pssh box or
+/// the content of a `mspr:pro` element.
+///
+/// The key IDs are converted from the little endian GUIDs PlayReady uses to UUID byte order.
+pub fn playready_key_ids(pro: &[u8]) -> error::Result<Vec<Kid>> {
+    let invalid = |message: &str| Error::Input {
+        message: format!("Provided data is not a valid PlayReady object: {message}."),
+    };
+    let record_count = pro
+        .get(4..6)
+        .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+        .ok_or_else(|| invalid("missing header"))?;
+    let mut offset = 6;
+    let mut header = None;
+    for _ in 0..record_count {
+        let (record_type, record_size) = pro
+            .get(offset..offset + 4)
+            .map(|bytes| {
+                (
+                    u16::from_le_bytes([bytes[0], bytes[1]]),
+                    u16::from_le_bytes([bytes[2], bytes[3]]) as usize,
+                )
+            })
+            .ok_or_else(|| invalid("truncated record header"))?;
+        offset += 4;
+        let record = pro
+            .get(offset..offset + record_size)
+            .ok_or_else(|| invalid("truncated record"))?;
+        offset += record_size;
+        if record_type == PLAYREADY_RIGHTS_MANAGEMENT_HEADER {
+            header = Some(record);
+            break;
+        }
+    }
+    let header = header.ok_or_else(|| invalid("missing rights management header"))?;
+    let header = String::from_utf16_lossy(
+        &header
+            .chunks_exact(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect::<Vec<u16>>(),
+    );
+
+    let key_ids = header_key_ids(&header)
+        .into_iter()
+        .map(|key_id| {
+            let guid: [u8; 16] = general_purpose::STANDARD
+                .decode(key_id.trim())
+                .ok()
+                .and_then(|guid| guid.try_into().ok())
+                .ok_or_else(|| invalid("malformed KID"))?;
+            let mut key_id = guid;
+            key_id[0..4].reverse();
+            key_id[4..6].reverse();
+            key_id[6..8].reverse();
+            Ok(Kid(key_id))
+        })
+        .collect::<error::Result<Vec<Kid>>>()?;
+    if key_ids.is_empty() {
+        return Err(invalid("no KID in rights management header"));
+    }
+    Ok(key_ids)
+}
+
+/// Collects the KIDs of a WRM header, either `<KID>...</KID>` (v4.0) or
+/// `<KID VALUE="..."/>` (v4.1 and later).
+fn header_key_ids(header: &str) -> Vec<&str> {
+    let mut key_ids = Vec::new();
+    let mut rest = header;
+    while let Some(start) = rest.find("<KID") {
+        rest = &rest[start + 4..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        if !tag.is_empty() && !tag.starts_with(char::is_whitespace) && !tag.starts_with('/') {
+            continue;
+        }
+        if let Some(value) = tag
+            .split("VALUE=\"")
+            .nth(1)
+            .and_then(|value| value.split('"').next())
+        {
+            key_ids.push(value);
+        } else if let Some(close) = rest[end + 1..].find("</KID>") {
+            key_ids.push(&rest[end + 1..end + 1 + close]);
+        }
+    }
+    key_ids
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .map(|bytes| u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //noinspection SpellCheckingInspection
+    const BITMOVIN_PSSH_B64: &str = "AAAAW3Bzc2gAAAAA7e+LqXnWSs6jyCfc1R0h7QAAADsIARIQ62dqu8s0Xpa7z2FmMPGj2hoNd2lkZXZpbmVfdGVzdCIQZmtqM2xqYVNkZmFsa3IzaioCSEQyAA==";
+
+    #[test]
+    fn parse_and_serialize() {
+        let pssh = Pssh::from_base64(BITMOVIN_PSSH_B64).unwrap();
+        assert!(pssh.is_widevine());
+        assert_eq!(pssh.version, 0);
+        assert_eq!(pssh.to_base64(), BITMOVIN_PSSH_B64);
+    }
+
+    #[test]
+    fn select_widevine_from_playready() {
+        let guid = [
+            0x44, 0x33, 0x22, 0x11, 0x66, 0x55, 0x88, 0x77, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE,
+            0xFF, 0x00,
+        ];
+        let header = format!(
+            "<WRMHEADER version=\"4.3.0.0\"><DATA><PROTECTINFO><KIDS><KID ALGID=\"AESCTR\" VALUE=\"{}\"></KID></KIDS></PROTECTINFO></DATA></WRMHEADER>",
+            general_purpose::STANDARD.encode(guid)
+        );
+        let header: Vec<u8> = header
+            .encode_utf16()
+            .flat_map(|unit| unit.to_le_bytes())
+            .collect();
+        let mut pro = Vec::new();
+        pro.extend_from_slice(&((header.len() + 10) as u32).to_le_bytes());
+        pro.extend_from_slice(&1u16.to_le_bytes());
+        pro.extend_from_slice(&PLAYREADY_RIGHTS_MANAGEMENT_HEADER.to_le_bytes());
+        pro.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        pro.extend_from_slice(&header);
+        let playready = Pssh {
+            version: 0,
+            flags: 0,
+            system_id: PLAYREADY_SYSTEM_ID,
+            key_ids: Vec::new(),
+            data: pro,
+        };
+
+        let key_id = Kid([
+            0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xAA, 0xBB, 0xCC, 0xDD, 0xEE,
+            0xFF, 0x00,
+        ]);
+        let pssh = Pssh::select_widevine(std::slice::from_ref(&playready)).unwrap();
+        assert_eq!(pssh, Pssh::new_widevine(vec![key_id], None, 0));
+
+        let widevine = Pssh::from_base64(BITMOVIN_PSSH_B64).unwrap();
+        let selected = Pssh::select_widevine(&[playready, widevine.clone()]).unwrap();
+        assert_eq!(selected, widevine);
+        assert!(Pssh::select_widevine(&[]).is_err());
+    }
+
+    #[test]
+    fn new_widevine_v1() {
+        let key_id = Kid([0x11; 16]);
+        let pssh = Pssh::new_widevine(vec![key_id], Some(b"content".to_vec()), 1);
+        let parsed = Pssh::from_bytes(&pssh.to_bytes()).unwrap();
+        assert_eq!(parsed, pssh);
+        assert_eq!(parsed.key_ids, vec![key_id]);
+        let pssh_data = WidevinePsshData::decode(parsed.data.as_slice()).unwrap();
+        assert_eq!(pssh_data.key_ids, vec![key_id.0.to_vec()]);
+        assert_eq!(pssh_data.content_id(), b"content");
+    }
+}
@@ -0,0 +1,251 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    error::{self, Error},
+    Cdm, KeyContainer,
+};
+use std::{
+    future::{ready, Future, Ready},
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// An operation of a [`Cdm`], the request type of [`CdmService`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CdmRequest {
+    Open,
+    Close {
+        session_id: Vec<u8>,
+    },
+    SetServiceCertificate {
+        session_id: Vec<u8>,
+        certificate: Vec<u8>,
+    },
+    GetLicenseChallenge {
+        session_id: Vec<u8>,
+        pssh: Vec<u8>,
+    },
+    ParseLicense {
+        session_id: Vec<u8>,
+        license: Vec<u8>,
+    },
+    GetKeys {
+        session_id: Vec<u8>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CdmResponse {
+    SessionId(Vec<u8>),
+    ProviderId(String),
+    Challenge(Vec<u8>),
+    Keys(Vec<KeyContainer>),
+    Done,
+}
+
+/// A tower [`Service`] executing [`CdmRequest`]s on a shared [`Cdm`].
+#[derive(Clone)]
+pub struct CdmService {
+    cdm: Arc<Mutex<Cdm>>,
+}
+
+impl CdmService {
+    pub fn new(cdm: Arc<Mutex<Cdm>>) -> CdmService {
+        CdmService { cdm }
+    }
+}
+
+impl Service<CdmRequest> for CdmService {
+    type Response = CdmResponse;
+    type Error = Error;
+    type Future = Ready<error::Result<CdmResponse>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<error::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: CdmRequest) -> Self::Future {
+        ready(lock(&self.cdm).and_then(|mut cdm| execute(&mut cdm, request)))
+    }
+}
+
+fn execute(cdm: &mut Cdm, request: CdmRequest) -> error::Result<CdmResponse> {
+    Ok(match request {
+        CdmRequest::Open => CdmResponse::SessionId(cdm.open()?),
+        CdmRequest::Close { session_id } => {
+            cdm.close(&session_id)?;
+            CdmResponse::Done
+        }
+        CdmRequest::SetServiceCertificate {
+            session_id,
+            certificate,
+        } => CdmResponse::ProviderId(cdm.set_service_certificate(&session_id, certificate)?),
+        CdmRequest::GetLicenseChallenge { session_id, pssh } => {
+            CdmResponse::Challenge(cdm.get_license_challenge(&session_id, pssh)?)
+        }
+        CdmRequest::ParseLicense {
+            session_id,
+            license,
+        } => {
+            cdm.parse_license(&session_id, license)?;
+            CdmResponse::Done
+        }
+        CdmRequest::GetKeys { session_id } => {
+            CdmResponse::Keys(cdm.get_keys(&session_id)?.to_vec())
+        }
+    })
+}
+
+/// Turns a service that posts challenges to a license server into a service that takes a
+/// PSSH and returns its content keys, running the whole session on a shared [`Cdm`].
+#[derive(Clone)]
+pub struct LicenseLayer {
+    cdm: Arc<Mutex<Cdm>>,
+    service_certificate: Option<Vec<u8>>,
+}
+
+impl LicenseLayer {
+    pub fn new(cdm: Arc<Mutex<Cdm>>) -> LicenseLayer {
+        LicenseLayer {
+            cdm,
+            service_certificate: None,
+        }
+    }
+
+    /// Installs `service_certificate` in every session, so client ids are sent encrypted.
+    pub fn with_service_certificate(mut self, service_certificate: Vec<u8>) -> LicenseLayer {
+        self.service_certificate = Some(service_certificate);
+        self
+    }
+}
+
+impl<S> Layer<S> for LicenseLayer {
+    type Service = LicenseService<S>;
+
+    fn layer(&self, inner: S) -> LicenseService<S> {
+        LicenseService {
+            inner,
+            cdm: self.cdm.clone(),
+            service_certificate: self.service_certificate.clone(),
+        }
+    }
+}
+
+/// The service created by [`LicenseLayer`], `inner` receives the challenge and responds
+/// with the license.
+#[derive(Clone)]
+pub struct LicenseService<S> {
+    inner: S,
+    cdm: Arc<Mutex<Cdm>>,
+    service_certificate: Option<Vec<u8>>,
+}
+
+impl<S> Service<Vec<u8>> for LicenseService<S>
+where
+    S: Service<Vec<u8>, Response = Vec<u8>> + Clone + Send + 'static,
+    S::Error: Into<Error>,
+    S::Future: Send,
+{
+    type Response = Vec<KeyContainer>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = error::Result<Vec<KeyContainer>>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<error::Result<()>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, pssh: Vec<u8>) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let cdm = self.cdm.clone();
+        let challenge = lock(&cdm).and_then(|mut cdm| {
+            let session_id = cdm.open()?;
+            let challenge = self
+                .service_certificate
+                .as_ref()
+                .map_or(Ok(String::new()), |service_certificate| {
+                    cdm.set_service_certificate(&session_id, service_certificate)
+                })
+                .and_then(|_| cdm.get_license_challenge(&session_id, pssh));
+            if challenge.is_err() {
+                cdm.close(&session_id)?;
+            }
+            Ok((session_id, challenge?))
+        });
+        Box::pin(async move {
+            let (session_id, challenge) = challenge?;
+            let license = inner.call(challenge).await.map_err(Into::into);
+            let mut cdm = lock(&cdm)?;
+            let keys = license.and_then(|license| {
+                cdm.parse_license(&session_id, license)?;
+                Ok(cdm.get_keys(&session_id)?.to_vec())
+            });
+            cdm.close(&session_id)?;
+            keys
+        })
+    }
+}
+
+fn lock(cdm: &Mutex<Cdm>) -> error::Result<MutexGuard<'_, Cdm>> {
+    cdm.lock().map_err(|_| Error::Internal {
+        message: "The CDM lock is poisoned.".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kid::Kid, license_protocol::SignedMessage, pssh::Pssh};
+    use prost::Message;
+
+    #[derive(Clone)]
+    struct RejectingServer(Arc<Mutex<Vec<Vec<u8>>>>);
+
+    impl Service<Vec<u8>> for RejectingServer {
+        type Response = Vec<u8>;
+        type Error = Error;
+        type Future = Ready<error::Result<Vec<u8>>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<error::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, challenge: Vec<u8>) -> Self::Future {
+            self.0.lock().unwrap().push(challenge);
+            ready(Ok(b"not a license".to_vec()))
+        }
+    }
+
+    #[tokio::test]
+    async fn license_layer() {
+        let device = crate::test_device();
+        let cdm = Arc::new(Mutex::new(Cdm::new(device)));
+        let pssh = Pssh::new_widevine(vec![Kid([0x11; 16])], None, 0);
+
+        let mut service = CdmService::new(cdm.clone());
+        let CdmResponse::SessionId(session_id) = service.call(CdmRequest::Open).await.unwrap()
+        else {
+            panic!("open did not return a session id");
+        };
+        let response = service
+            .call(CdmRequest::GetLicenseChallenge {
+                session_id,
+                pssh: pssh.to_bytes(),
+            })
+            .await
+            .unwrap();
+        assert!(matches!(response, CdmResponse::Challenge(_)));
+
+        let challenges = Arc::new(Mutex::new(Vec::new()));
+        let mut service = LicenseLayer::new(cdm.clone()).layer(RejectingServer(challenges.clone()));
+        assert!(service.call(pssh.to_bytes()).await.is_err());
+        let challenges = challenges.lock().unwrap();
+        assert_eq!(challenges.len(), 1);
+        assert!(SignedMessage::decode(challenges[0].as_slice()).is_ok());
+        assert_eq!(cdm.lock().unwrap().open_sessions(), 1);
+    }
+}
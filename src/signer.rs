@@ -0,0 +1,357 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::error::{self, Error};
+#[cfg(feature = "experimental-ecc")]
+use openssl::{derive::Deriver, ec::EcKey};
+use openssl::{
+    hash::MessageDigest,
+    pkey::{Id, PKey, Private},
+    rsa::{Padding, Rsa},
+    sha::sha256,
+    sign::{RsaPssSaltlen, Signer},
+};
+use std::{fmt, future::Future, pin::Pin};
+
+/// Size of the session key derived from an ephemeral ECC public key.
+#[cfg(feature = "experimental-ecc")]
+const ECC_SESSION_KEY_SIZE: usize = 16;
+
+/// The private key operations of a device, so that the key can live in an HSM,
+/// a TPM or a remote service instead of process memory.
+pub trait DeviceSigner: Send + Sync {
+    /// Signs a license request with RSASSA-PSS (SHA-1, 20 byte salt by default).
+    fn sign(&self, message: &[u8]) -> error::Result<Vec<u8>>;
+
+    /// Decrypts the session key of a license with RSAES-OAEP (SHA-1).
+    fn decrypt(&self, ciphertext: &[u8]) -> error::Result<Vec<u8>>;
+
+    /// Derives the session key of a license from its ephemeral ECC public key (DER encoded
+    /// SubjectPublicKeyInfo), only devices with ECC keys support this.
+    fn derive_session_key(&self, _ephemeral_public_key: &[u8]) -> error::Result<Vec<u8>> {
+        Err(ecc_unsupported())
+    }
+
+    /// The public key in the format of the device certificate (PKCS#1 DER for RSA keys),
+    /// used to check that the key belongs to the client id. `None` skips the check.
+    fn public_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+pub type SignerFuture<'a> = Pin<Box<dyn Future<Output = error::Result<Vec<u8>>> + Send + 'a>>;
+
+/// A [`DeviceSigner`] whose operations are awaited, for network or hardware backed keys
+/// that should not block the async runtime.
+pub trait AsyncDeviceSigner: Send + Sync {
+    fn sign<'a>(&'a self, message: &'a [u8]) -> SignerFuture<'a>;
+
+    fn decrypt<'a>(&'a self, ciphertext: &'a [u8]) -> SignerFuture<'a>;
+
+    fn derive_session_key<'a>(&'a self, _ephemeral_public_key: &'a [u8]) -> SignerFuture<'a> {
+        Box::pin(std::future::ready(Err(ecc_unsupported())))
+    }
+}
+
+/// Loads a PEM encoded RSA or ECC device private key into the matching signer, ECC keys
+/// need the `experimental-ecc` feature.
+pub fn from_pem(private_key: &[u8]) -> error::Result<Box<dyn DeviceSigner>> {
+    let private_key = PKey::private_key_from_pem(private_key)
+        .map_err(|stack| openssl_error("Failed to parse the device private key", stack))?;
+    let load_error = |stack| openssl_error("Failed to load the device private key", stack);
+    match private_key.id() {
+        Id::RSA => {
+            let private_key = private_key.rsa().map_err(load_error)?;
+            Ok(Box::new(RsaDeviceSigner::new(private_key)?))
+        }
+        #[cfg(feature = "experimental-ecc")]
+        Id::EC => {
+            let private_key = private_key.ec_key().map_err(load_error)?;
+            Ok(Box::new(EccDeviceSigner::new(private_key)?))
+        }
+        #[cfg(not(feature = "experimental-ecc"))]
+        Id::EC => Err(Error::Input {
+            message: "ECC device keys need the experimental-ecc feature.".to_string(),
+        }),
+        id => Err(Error::Input {
+            message: format!("Unsupported device private key type {id:?}."),
+        }),
+    }
+}
+
+/// Salt length of RSASSA-PSS license request signatures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PssSaltLength {
+    Bytes(u32),
+    /// As long as the digest.
+    DigestLength,
+    /// The longest salt the key size allows.
+    Maximum,
+}
+
+impl Default for PssSaltLength {
+    /// 20 bytes, like the Widevine CDM.
+    fn default() -> PssSaltLength {
+        PssSaltLength::Bytes(20)
+    }
+}
+
+impl PssSaltLength {
+    fn openssl(self) -> RsaPssSaltlen {
+        match self {
+            PssSaltLength::Bytes(length) => RsaPssSaltlen::custom(length as i32),
+            PssSaltLength::DigestLength => RsaPssSaltlen::DIGEST_LENGTH,
+            PssSaltLength::Maximum => RsaPssSaltlen::MAXIMUM_LENGTH,
+        }
+    }
+}
+
+/// A [`DeviceSigner`] backed by an RSA private key in memory.
+pub struct RsaDeviceSigner {
+    private_key: Rsa<Private>,
+    private_key_pkey: PKey<Private>,
+    pss_salt_length: PssSaltLength,
+}
+
+impl RsaDeviceSigner {
+    pub fn new(private_key: Rsa<Private>) -> error::Result<RsaDeviceSigner> {
+        let private_key_pkey = PKey::from_rsa(private_key.clone())
+            .map_err(|stack| openssl_error("Failed to load the device private key", stack))?;
+        Ok(RsaDeviceSigner {
+            private_key,
+            private_key_pkey,
+            pss_salt_length: PssSaltLength::default(),
+        })
+    }
+
+    /// Signs with another PSS salt length, for keys or servers that expect one.
+    pub fn with_pss_salt_length(mut self, pss_salt_length: PssSaltLength) -> RsaDeviceSigner {
+        self.pss_salt_length = pss_salt_length;
+        self
+    }
+
+    pub fn from_pem(private_key: &[u8]) -> error::Result<RsaDeviceSigner> {
+        let private_key = Rsa::private_key_from_pem(private_key)
+            .map_err(|stack| openssl_error("Failed to parse the device private key", stack))?;
+        RsaDeviceSigner::new(private_key)
+    }
+}
+
+impl fmt::Debug for RsaDeviceSigner {
+    /// Only prints the key size and a fingerprint of the public key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let public_key = self
+            .private_key
+            .public_key_to_der_pkcs1()
+            .unwrap_or_default();
+        f.debug_struct("RsaDeviceSigner")
+            .field("bits", &(self.private_key.size() * 8))
+            .field("public_key", &fingerprint(&public_key))
+            .field("pss_salt_length", &self.pss_salt_length)
+            .finish()
+    }
+}
+
+impl DeviceSigner for RsaDeviceSigner {
+    fn sign(&self, message: &[u8]) -> error::Result<Vec<u8>> {
+        let sign = || {
+            let mut signer = Signer::new(MessageDigest::sha1(), &self.private_key_pkey)?;
+            signer.set_rsa_padding(Padding::PKCS1_PSS)?;
+            signer.set_rsa_pss_saltlen(self.pss_salt_length.openssl())?;
+            signer.update(message)?;
+            signer.sign_to_vec()
+        };
+        sign().map_err(|stack| openssl_error("Failed to sign the license request", stack))
+    }
+
+    fn decrypt(&self, ciphertext: &[u8]) -> error::Result<Vec<u8>> {
+        let mut plaintext = vec![0; self.private_key.size() as usize];
+        let length = self
+            .private_key
+            .private_decrypt(ciphertext, &mut plaintext, Padding::PKCS1_OAEP)
+            .map_err(|stack| openssl_error("Failed to decrypt the session key", stack))?;
+        plaintext.truncate(length);
+        Ok(plaintext)
+    }
+
+    fn public_key(&self) -> Option<Vec<u8>> {
+        self.private_key.public_key_to_der_pkcs1().ok()
+    }
+}
+
+/// A [`DeviceSigner`] backed by an ECC private key in memory, for devices provisioned
+/// with ECC certificates. Requests are signed with ECDSA (SHA-256) and session keys are
+/// derived with ECDH.
+///
+/// Experimental (feature `experimental-ecc`): the session key is the first 16 bytes of
+/// the SHA-256 digest of the ECDH shared secret. There is no public specification of this
+/// derivation and it has not been checked against a license from a real ECC device, so
+/// licenses may fail to decrypt until it is.
+#[cfg(feature = "experimental-ecc")]
+pub struct EccDeviceSigner {
+    private_key: PKey<Private>,
+}
+
+#[cfg(feature = "experimental-ecc")]
+impl EccDeviceSigner {
+    pub fn new(private_key: EcKey<Private>) -> error::Result<EccDeviceSigner> {
+        let private_key = PKey::from_ec_key(private_key)
+            .map_err(|stack| openssl_error("Failed to load the device private key", stack))?;
+        Ok(EccDeviceSigner { private_key })
+    }
+
+    pub fn from_pem(private_key: &[u8]) -> error::Result<EccDeviceSigner> {
+        let private_key = EcKey::private_key_from_pem(private_key)
+            .map_err(|stack| openssl_error("Failed to parse the device private key", stack))?;
+        EccDeviceSigner::new(private_key)
+    }
+}
+
+#[cfg(feature = "experimental-ecc")]
+impl fmt::Debug for EccDeviceSigner {
+    /// Only prints the key size and a fingerprint of the public key.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let public_key = self.private_key.public_key_to_der().unwrap_or_default();
+        f.debug_struct("EccDeviceSigner")
+            .field("bits", &self.private_key.bits())
+            .field("public_key", &fingerprint(&public_key))
+            .finish()
+    }
+}
+
+#[cfg(feature = "experimental-ecc")]
+impl DeviceSigner for EccDeviceSigner {
+    fn sign(&self, message: &[u8]) -> error::Result<Vec<u8>> {
+        let sign = || {
+            let mut signer = Signer::new(MessageDigest::sha256(), &self.private_key)?;
+            signer.update(message)?;
+            signer.sign_to_vec()
+        };
+        sign().map_err(|stack| openssl_error("Failed to sign the license request", stack))
+    }
+
+    fn decrypt(&self, _ciphertext: &[u8]) -> error::Result<Vec<u8>> {
+        Err(Error::Input {
+            message: "ECC devices cannot decrypt wrapped session keys.".to_string(),
+        })
+    }
+
+    fn derive_session_key(&self, ephemeral_public_key: &[u8]) -> error::Result<Vec<u8>> {
+        let derive = || {
+            let ephemeral_public_key = PKey::public_key_from_der(ephemeral_public_key)?;
+            let mut deriver = Deriver::new(&self.private_key)?;
+            deriver.set_peer(&ephemeral_public_key)?;
+            deriver.derive_to_vec()
+        };
+        let shared_secret =
+            derive().map_err(|stack| openssl_error("Failed to derive the session key", stack))?;
+        Ok(sha256(&shared_secret)[..ECC_SESSION_KEY_SIZE].to_vec())
+    }
+}
+
+/// Short SHA-256 fingerprint of `data`, safe to log.
+pub(crate) fn fingerprint(data: &[u8]) -> String {
+    format!("sha256:{}", hex::encode(&sha256(data)[..8]))
+}
+
+fn ecc_unsupported() -> Error {
+    Error::Input {
+        message: "This device does not support ECC session keys.".to_string(),
+    }
+}
+
+fn openssl_error(message: &str, stack: openssl::error::ErrorStack) -> Error {
+    Error::OpenSSL {
+        message: message.to_string(),
+        stack,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use openssl::{
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        sign::Verifier,
+    };
+
+    #[test]
+    fn rsa_device_signer() {
+        let private_key = Rsa::generate(2048).unwrap();
+        let public_key = PKey::from_rsa(
+            Rsa::public_key_from_der_pkcs1(&private_key.public_key_to_der_pkcs1().unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        let signer = RsaDeviceSigner::new(private_key.clone()).unwrap();
+
+        let signature = signer.sign(b"license request").unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha1(), &public_key).unwrap();
+        verifier.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        verifier
+            .set_rsa_pss_saltlen(RsaPssSaltlen::custom(20))
+            .unwrap();
+        verifier.update(b"license request").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        let mut ciphertext = vec![0; private_key.size() as usize];
+        private_key
+            .public_encrypt(&[0x42; 16], &mut ciphertext, Padding::PKCS1_OAEP)
+            .unwrap();
+        assert_eq!(signer.decrypt(&ciphertext).unwrap(), vec![0x42; 16]);
+
+        let signer = signer.with_pss_salt_length(PssSaltLength::Maximum);
+        let signature = signer.sign(b"license request").unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha1(), &public_key).unwrap();
+        verifier.set_rsa_padding(Padding::PKCS1_PSS).unwrap();
+        verifier
+            .set_rsa_pss_saltlen(RsaPssSaltlen::custom(256 - 20 - 2))
+            .unwrap();
+        verifier.update(b"license request").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+    }
+
+    #[cfg(feature = "experimental-ecc")]
+    #[test]
+    fn ecc_device_signer() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let private_key = EcKey::generate(&group).unwrap();
+        let public_key = PKey::public_key_from_der(
+            &PKey::from_ec_key(private_key.clone())
+                .unwrap()
+                .public_key_to_der()
+                .unwrap(),
+        )
+        .unwrap();
+        let signer = from_pem(&private_key.private_key_to_pem().unwrap()).unwrap();
+
+        let signature = signer.sign(b"license request").unwrap();
+        let mut verifier = Verifier::new(MessageDigest::sha256(), &public_key).unwrap();
+        verifier.update(b"license request").unwrap();
+        assert!(verifier.verify(&signature).unwrap());
+
+        let ephemeral_key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let mut deriver = Deriver::new(&ephemeral_key).unwrap();
+        deriver.set_peer(&public_key).unwrap();
+        let session_key = sha256(&deriver.derive_to_vec().unwrap())[..16].to_vec();
+        assert_eq!(
+            signer
+                .derive_session_key(&ephemeral_key.public_key_to_der().unwrap())
+                .unwrap(),
+            session_key
+        );
+        assert!(signer.decrypt(&[0; 32]).is_err());
+    }
+
+    #[cfg(not(feature = "experimental-ecc"))]
+    #[test]
+    fn ecc_needs_feature() {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let private_key = EcKey::generate(&group).unwrap();
+        assert!(matches!(
+            from_pem(&private_key.private_key_to_pem().unwrap()),
+            Err(Error::Input { .. })
+        ));
+    }
+}
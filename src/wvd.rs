@@ -0,0 +1,186 @@
+// Copyright Frooastside
+// SPDX-License-Identifier: MIT
+
+use crate::{
+    credentials::CredentialSource,
+    error::{self, Error},
+    license_protocol::ClientIdentification,
+};
+use openssl::pkey::PKey;
+use prost::Message;
+use std::{fmt, fs, path::Path};
+
+const MAGIC: &[u8; 3] = b"WVD";
+const VERSION: u8 = 2;
+
+/// The device type stored in a `.wvd` file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WvdDeviceType {
+    Chrome = 1,
+    Android = 2,
+}
+
+/// A device in pywidevine's `.wvd` format. Version 1 files are read as well, their separate
+/// VMP blob is moved into the client id like pywidevine's migration does.
+#[derive(Clone, PartialEq)]
+pub struct WvdDevice {
+    pub device_type: WvdDeviceType,
+    pub security_level: u8,
+    /// Reserved, written as is.
+    pub flags: u8,
+    /// DER encoded private key (PKCS#1 for RSA keys).
+    pub private_key: Vec<u8>,
+    pub client_id: ClientIdentification,
+}
+
+impl WvdDevice {
+    pub fn from_bytes(wvd: &[u8]) -> error::Result<WvdDevice> {
+        let invalid = |message: &str| Error::Input {
+            message: format!("Provided data is not a valid wvd file: {message}."),
+        };
+        if wvd.len() < 7 || &wvd[0..3] != MAGIC {
+            return Err(invalid("missing WVD header"));
+        }
+        let version = wvd[3];
+        if !(1..=VERSION).contains(&version) {
+            return Err(invalid("unsupported version"));
+        }
+        let device_type = match wvd[4] {
+            1 => WvdDeviceType::Chrome,
+            2 => WvdDeviceType::Android,
+            _ => return Err(invalid("unknown device type")),
+        };
+        let security_level = wvd[5];
+        let flags = wvd[6];
+        let mut offset = 7;
+        let mut read_field = |name: &str| -> error::Result<&[u8]> {
+            let length = wvd
+                .get(offset..offset + 2)
+                .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+                .ok_or_else(|| invalid(&format!("truncated {name} length")))?;
+            let field = wvd
+                .get(offset + 2..offset + 2 + length)
+                .ok_or_else(|| invalid(&format!("truncated {name}")))?;
+            offset += 2 + length;
+            Ok(field)
+        };
+        let private_key = read_field("private key")?.to_vec();
+        let mut client_id =
+            ClientIdentification::decode(read_field("client id")?).map_err(|error| {
+                Error::Input {
+                    message: format!("The client id of the wvd file is invalid: {error}"),
+                }
+            })?;
+        if version == 1 {
+            let vmp_data = read_field("vmp data")?;
+            if !vmp_data.is_empty() && client_id.vmp_data.is_none() {
+                client_id.vmp_data = Some(vmp_data.to_vec());
+            }
+        }
+        Ok(WvdDevice {
+            device_type,
+            security_level,
+            flags,
+            private_key,
+            client_id,
+        })
+    }
+
+    /// Serializes the device as a `.wvd` version 2 file.
+    pub fn to_bytes(&self) -> error::Result<Vec<u8>> {
+        let client_id = self.client_id.encode_to_vec();
+        let mut wvd = MAGIC.to_vec();
+        wvd.extend([
+            VERSION,
+            self.device_type as u8,
+            self.security_level,
+            self.flags,
+        ]);
+        for (name, field) in [
+            ("private key", &self.private_key),
+            ("client id", &client_id),
+        ] {
+            let length = u16::try_from(field.len()).map_err(|_| Error::Input {
+                message: format!("The {name} is too long for a wvd file."),
+            })?;
+            wvd.extend(length.to_be_bytes());
+            wvd.extend(field);
+        }
+        Ok(wvd)
+    }
+
+    pub fn read(path: &Path) -> error::Result<WvdDevice> {
+        let wvd = fs::read(path).map_err(|error| Error::Input {
+            message: format!("Failed to read {}: {error}", path.display()),
+        })?;
+        WvdDevice::from_bytes(&wvd)
+    }
+
+    pub fn write(&self, path: &Path) -> error::Result<()> {
+        fs::write(path, self.to_bytes()?).map_err(|error| Error::Input {
+            message: format!("Failed to write {}: {error}", path.display()),
+        })
+    }
+}
+
+impl fmt::Debug for WvdDevice {
+    /// Leaves out the private key, so devices can be logged safely.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WvdDevice")
+            .field("device_type", &self.device_type)
+            .field("security_level", &self.security_level)
+            .field("flags", &self.flags)
+            .field("client_id", &self.client_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CredentialSource for WvdDevice {
+    fn private_key(&self) -> error::Result<Vec<u8>> {
+        PKey::private_key_from_der(&self.private_key)
+            .and_then(|private_key| private_key.private_key_to_pem_pkcs8())
+            .map_err(|stack| Error::OpenSSL {
+                message: "Failed to load the private key of the wvd file".to_string(),
+                stack,
+            })
+    }
+
+    fn client_id(&self) -> error::Result<Vec<u8>> {
+        Ok(self.client_id.encode_to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Device;
+    use openssl::rsa::Rsa;
+
+    #[test]
+    fn wvd_round_trip() {
+        let device = WvdDevice {
+            device_type: WvdDeviceType::Android,
+            security_level: 3,
+            flags: 0,
+            private_key: Rsa::generate(2048).unwrap().private_key_to_der().unwrap(),
+            client_id: ClientIdentification {
+                vmp_data: Some(vec![0x01; 8]),
+                ..Default::default()
+            },
+        };
+        let wvd = device.to_bytes().unwrap();
+        assert_eq!(&wvd[0..7], b"WVD\x02\x02\x03\x00");
+        assert_eq!(WvdDevice::from_bytes(&wvd).unwrap(), device);
+        assert!(Device::from_source(&device).is_ok());
+        assert!(!format!("{device:?}").contains("private_key"));
+        assert!(WvdDevice::from_bytes(&wvd[..wvd.len() - 1]).is_err());
+
+        let client_id = ClientIdentification::default().encode_to_vec();
+        let mut wvd_v1 = b"WVD\x01\x02\x03\x00".to_vec();
+        for field in [&device.private_key, &client_id, &vec![0x01; 8]] {
+            wvd_v1.extend((field.len() as u16).to_be_bytes());
+            wvd_v1.extend(field);
+        }
+        assert_eq!(WvdDevice::from_bytes(&wvd_v1).unwrap(), device);
+    }
+}